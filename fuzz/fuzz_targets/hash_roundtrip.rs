@@ -0,0 +1,126 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Feed arbitrary byte and bit messages to every hasher.
+//!
+//! The input is framed as `[out_len, split, drop_bits, message...]`:
+//! * `out_len` is the number of bytes squeezed from the XOFs,
+//! * `split` picks where the message is split across two updates,
+//! * `drop_bits` (mod 8) trailing bits are removed from the message for the bit-oriented run.
+//!
+//! Every hasher must not panic, and absorbing the message in one update must give the same
+//! result as absorbing it in two, and as the one-shot helpers.  XOF output squeezed in two
+//! calls must match a single squeeze.
+//!
+//! Run with `cargo +nightly fuzz run hash_roundtrip` from the crate root.  The corpus is
+//! seeded with the NIST example messages (0, 5, 30, 1600, 1605 and 1630 bits).
+
+#![no_main]
+
+use anyhow::Result;
+use libfuzzer_sys::fuzz_target;
+use shashasha::{
+    BitSlice, BitVec, Hasher, HasherBits, Lsb0, Sha3_224, Sha3_256, Sha3_384, Sha3_512, Shake128,
+    Shake256, XofHasher, XofHasherBits, sha3_224_bits, sha3_256_bits, sha3_384_bits, sha3_512_bits,
+    shake128_bits, shake256_bits,
+};
+
+struct Input<'a> {
+    message: &'a [u8],
+    split: usize,
+    bits: &'a BitSlice<u8, Lsb0>,
+    bit_split: usize,
+    out_len: usize,
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let [out_len, split, drop_bits, message @ ..] = data {
+        let bits = BitVec::<u8, Lsb0>::from_slice(message);
+        let len = bits.len().saturating_sub(usize::from(*drop_bits % 8));
+        let input = Input {
+            message,
+            split: usize::from(*split).min(message.len()),
+            bits: &bits[..len],
+            bit_split: (usize::from(*split) * 7).min(len),
+            out_len: usize::from(*out_len),
+        };
+
+        check(&input).expect("hashing failed");
+    }
+});
+
+fn check(input: &Input<'_>) -> Result<()> {
+    check_fixed(input, Sha3_224::new, sha3_224_bits)?;
+    check_fixed(input, Sha3_256::new, sha3_256_bits)?;
+    check_fixed(input, Sha3_384::new, sha3_384_bits)?;
+    check_fixed(input, Sha3_512::new, sha3_512_bits)?;
+    check_xof(input, Shake128::new, shake128_bits)?;
+    check_xof(input, Shake256::new, shake256_bits)?;
+    Ok(())
+}
+
+fn check_fixed<const D: usize, H: Hasher<D> + HasherBits<D>>(
+    input: &Input<'_>,
+    new: fn() -> H,
+    oneshot_bits: fn(&BitSlice<u8, Lsb0>) -> Result<[u8; D]>,
+) -> Result<()> {
+    let (head, tail) = input.message.split_at(input.split);
+    let mut whole = [0u8; D];
+    let mut hasher = new();
+    hasher.update(input.message)?;
+    hasher.finalize(&mut whole)?;
+
+    let mut pieces = [0u8; D];
+    let mut hasher = new();
+    hasher.update(head)?;
+    hasher.update(tail)?;
+    hasher.finalize(&mut pieces)?;
+    assert_eq!(whole, pieces);
+
+    let (head, tail) = input.bits.split_at(input.bit_split);
+    let mut hasher = new();
+    hasher.update_bits(head)?;
+    hasher.update_bits(tail)?;
+    hasher.finalize(&mut pieces)?;
+    assert_eq!(oneshot_bits(input.bits)?, pieces);
+    Ok(())
+}
+
+fn check_xof<H: XofHasher + XofHasherBits>(
+    input: &Input<'_>,
+    new: fn() -> H,
+    oneshot_bits: fn(&BitSlice<u8, Lsb0>, usize) -> Result<Vec<u8>>,
+) -> Result<()> {
+    let out_len = input.out_len;
+    let (head, tail) = input.message.split_at(input.split);
+    let mut whole = vec![0u8; out_len];
+    let mut hasher = new();
+    hasher.update(input.message)?;
+    hasher.finalize()?;
+    hasher.get_bytes(&mut whole, out_len)?;
+
+    // squeeze the same output in two calls
+    let mut pieces = vec![0u8; out_len];
+    let (first, second) = pieces.split_at_mut(out_len / 2);
+    let mut hasher = new();
+    hasher.update(head)?;
+    hasher.update(tail)?;
+    hasher.finalize()?;
+    hasher.get_bytes(first, first.len())?;
+    hasher.get_bytes(second, second.len())?;
+    assert_eq!(whole, pieces);
+
+    let (head, tail) = input.bits.split_at(input.bit_split);
+    let mut hasher = new();
+    hasher.update_bits(head)?;
+    hasher.update_bits(tail)?;
+    hasher.finalize()?;
+    hasher.get_bytes(&mut pieces, out_len)?;
+    assert_eq!(oneshot_bits(input.bits, out_len)?, pieces);
+    Ok(())
+}
@@ -0,0 +1,215 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! The update helpers shared by [`Hasher`](crate::Hasher) and
+//! [`XofHasher`](crate::XofHasher) (and their `*Bits` counterparts).
+//!
+//! Each helper is written once here against the `update` (or `update_bits`) of the trait
+//! calling it, passed in as a closure, so the two traits cannot drift apart.
+
+use std::io::{self, IoSlice, Read, Write};
+
+use anyhow::Result;
+use bitvec::{order::Lsb0, slice::BitSlice};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+use crate::{
+    Sha3Error,
+    constants::SHAKE_128_RATE,
+    sp800_185::encoding::left_encode,
+    utils::{hex_to_bytes, varint},
+};
+
+// The size of the stack buffers used by `repeated` and `reader_bits`, the largest rate
+// (SHAKE128) in bytes
+pub(crate) const BLOCK_BYTES: usize = SHAKE_128_RATE / 8;
+// The size of the buffers used by `read_some` and `read_to_end`, and so the most a single
+// `read_some` call absorbs
+const READ_BYTES: usize = 8192;
+
+pub(crate) fn io_slices<F>(mut update: F, bufs: &[IoSlice<'_>]) -> Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+{
+    for buf in bufs {
+        update(buf)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn repeated<F>(mut update: F, byte: u8, count: usize) -> Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+{
+    let block = [byte; BLOCK_BYTES];
+    let mut remaining = count;
+    while remaining > 0 {
+        let len = remaining.min(BLOCK_BYTES);
+        update(&block[..len])?;
+        remaining -= len;
+    }
+    Ok(())
+}
+
+pub(crate) fn hex<F>(mut update: F, s: &str) -> Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+{
+    update(&hex_to_bytes(s)?)
+}
+
+pub(crate) fn u32_le<F>(mut update: F, value: u32) -> Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+{
+    update(&value.to_le_bytes())
+}
+
+pub(crate) fn u32_be<F>(mut update: F, value: u32) -> Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+{
+    update(&value.to_be_bytes())
+}
+
+pub(crate) fn u64_le<F>(mut update: F, value: u64) -> Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+{
+    update(&value.to_le_bytes())
+}
+
+pub(crate) fn u64_be<F>(mut update: F, value: u64) -> Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+{
+    update(&value.to_be_bytes())
+}
+
+pub(crate) fn leb128<F>(mut update: F, value: u64) -> Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+{
+    update(&varint(value))
+}
+
+pub(crate) fn matrix<F>(mut update: F, rows: &[&[u8]]) -> Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+{
+    update(&left_encode(rows.len()))?;
+    for row in rows {
+        update(&left_encode(row.len()))?;
+        update(row)?;
+    }
+    Ok(())
+}
+
+// The buffer is zeroed even if the update fails
+#[cfg(feature = "zeroize")]
+pub(crate) fn and_zeroize<F>(mut update: F, data: &mut [u8]) -> Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+{
+    let result = update(data);
+    data.zeroize();
+    result
+}
+
+// Exactly one read of at most `READ_BYTES`; read errors are returned before anything is
+// absorbed
+pub(crate) fn read_some<F, R>(mut update: F, reader: &mut R, max_bytes: usize) -> io::Result<usize>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+    R: Read,
+{
+    let mut buf = [0u8; READ_BYTES];
+    let count = reader.read(&mut buf[..max_bytes.min(READ_BYTES)])?;
+    update(&buf[..count]).map_err(io::Error::other)?;
+    Ok(count)
+}
+
+// `data` is written first, so a failed write absorbs nothing
+pub(crate) fn tee<F, W>(mut update: F, data: &[u8], sink: &mut W) -> io::Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+    W: Write,
+{
+    sink.write_all(data)?;
+    update(data).map_err(io::Error::other)
+}
+
+#[cfg(feature = "tokio")]
+pub(crate) async fn read_to_end<F, R>(mut update: F, reader: &mut R) -> io::Result<()>
+where
+    F: FnMut(&[u8]) -> Result<()>,
+    R: AsyncRead + Unpin,
+{
+    let mut buf = vec![0u8; READ_BYTES];
+    loop {
+        let count = reader.read(&mut buf).await?;
+        if count == 0 {
+            return Ok(());
+        }
+        update(&buf[..count]).map_err(io::Error::other)?;
+    }
+}
+
+pub(crate) fn bits_iter<'a, F, I>(mut update_bits: F, iter: I) -> Result<()>
+where
+    F: FnMut(&BitSlice<u8, Lsb0>) -> Result<()>,
+    I: IntoIterator<Item = &'a BitSlice<u8, Lsb0>>,
+{
+    for chunk in iter {
+        update_bits(chunk)?;
+    }
+    Ok(())
+}
+
+// Read `total_bits.div_ceil(8)` bytes from `reader` a block at a time, passing the first
+// `total_bits` of their bits to `update_bits`.  Only the final chunk can end mid-byte.
+pub(crate) fn reader_bits<F, R>(
+    mut update_bits: F,
+    reader: &mut R,
+    total_bits: usize,
+) -> io::Result<()>
+where
+    F: FnMut(&BitSlice<u8, Lsb0>) -> Result<()>,
+    R: Read,
+{
+    let mut buf = [0u8; BLOCK_BYTES];
+    let mut remaining = total_bits;
+    while remaining > 0 {
+        let bits = remaining.min(BLOCK_BYTES * 8);
+        let len = bits.div_ceil(8);
+        reader.read_exact(&mut buf[..len])?;
+        update_bits(&BitSlice::from_slice(&buf[..len])[..bits]).map_err(io::Error::other)?;
+        remaining -= bits;
+    }
+    Ok(())
+}
+
+pub(crate) fn trailing_bits<F>(
+    mut update_bits: F,
+    full_bytes: &[u8],
+    trailing: u8,
+    trailing_bits: usize,
+) -> Result<()>
+where
+    F: FnMut(&BitSlice<u8, Lsb0>) -> Result<()>,
+{
+    if trailing_bits > 8 {
+        Err(Sha3Error::InvalidTrailingBits(trailing_bits).into())
+    } else {
+        update_bits(BitSlice::from_slice(full_bytes))?;
+        update_bits(&BitSlice::from_element(&trailing)[..trailing_bits])
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Heap buffers aligned for SIMD consumers
+
+use std::ops::{Deref, DerefMut};
+
+/// The alignment, in bytes, of an [`AlignedBytes`] buffer (enough for 256-bit vectors)
+pub const SIMD_ALIGN_BYTES: usize = 32;
+
+/// A heap buffer of bytes whose first byte is aligned to [`SIMD_ALIGN_BYTES`].
+///
+/// The buffer dereferences to `[u8]`, so it can be used anywhere a byte slice can.  It is
+/// returned by [`XofHasher::get_bytes_aligned`](crate::XofHasher::get_bytes_aligned) for
+/// consumers that load the output with aligned vector instructions.
+///
+/// The alignment is obtained without `unsafe` code by over-allocating and starting the
+/// buffer at the first aligned byte, so up to `SIMD_ALIGN_BYTES - 1` extra bytes are
+/// allocated.
+#[derive(Debug)]
+pub struct AlignedBytes {
+    storage: Box<[u8]>,
+    offset: usize,
+    len: usize,
+}
+
+impl AlignedBytes {
+    /// Allocate a zeroed, aligned buffer of `len` bytes.
+    #[must_use]
+    pub fn zeroed(len: usize) -> Self {
+        let storage = vec![0u8; len.saturating_add(SIMD_ALIGN_BYTES - 1)].into_boxed_slice();
+        let misalignment = storage.as_ptr().addr() % SIMD_ALIGN_BYTES;
+        let offset = (SIMD_ALIGN_BYTES - misalignment) % SIMD_ALIGN_BYTES;
+        Self {
+            storage,
+            offset,
+            len,
+        }
+    }
+}
+
+// A derived clone would keep the offset, which is only correct for the original allocation
+impl Clone for AlignedBytes {
+    fn clone(&self) -> Self {
+        let mut clone = Self::zeroed(self.len);
+        clone.copy_from_slice(self);
+        clone
+    }
+}
+
+impl Deref for AlignedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.storage[self.offset..self.offset + self.len]
+    }
+}
+
+impl DerefMut for AlignedBytes {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.storage[self.offset..self.offset + self.len]
+    }
+}
+
+impl PartialEq for AlignedBytes {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for AlignedBytes {}
+
+#[cfg(test)]
+mod test {
+    use super::{AlignedBytes, SIMD_ALIGN_BYTES};
+
+    #[test]
+    fn test_zeroed_is_aligned() {
+        for len in [0, 1, 31, 32, 33, 1000] {
+            let mut bytes = AlignedBytes::zeroed(len);
+            assert_eq!(len, bytes.len());
+            assert_eq!(0, bytes.as_ptr().addr() % SIMD_ALIGN_BYTES);
+            assert!(bytes.iter().all(|&byte| byte == 0));
+            bytes.fill(0xff);
+            // A clone has its own, independently aligned, storage
+            let clone = bytes.clone();
+            assert_eq!(0, clone.as_ptr().addr() % SIMD_ALIGN_BYTES);
+            assert_eq!(bytes, clone);
+        }
+    }
+}
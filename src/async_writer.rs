@@ -0,0 +1,89 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! A tokio `AsyncWrite` sink that hashes everything written to it
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use anyhow::Result;
+use tokio::io::AsyncWrite;
+
+use crate::{Hasher, SHA3_256_BYTES, Sha3_256};
+
+/// An asynchronous sink that absorbs everything written to it into a SHA3-256 hasher.
+///
+/// Writes never block: each `poll_write` absorbs the whole buffer and is immediately
+/// ready, and flushing and shutting down do nothing.  This lets an async stream be hashed
+/// with `tokio::io::copy`, without buffering it.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{Sha3Writer, sha3_256};
+/// # use tokio::io::AsyncWriteExt;
+/// # pub fn main() -> Result<()> {
+/// # tokio::runtime::Builder::new_current_thread().build()?.block_on(async {
+/// let mut writer = Sha3Writer::new();
+/// writer.write_all(b"Hello, world!").await?;
+/// assert_eq!(sha3_256(b"Hello, world!")?, writer.finalize()?);
+/// #     Ok(())
+/// # })
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Sha3Writer {
+    inner: Sha3_256,
+}
+
+impl Sha3Writer {
+    /// Create a new writer with an empty SHA3-256 hasher.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: Sha3_256::new(),
+        }
+    }
+
+    /// Finalize the hash of everything written and return the digest.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying SHA3-256 finalization fails.
+    ///
+    pub fn finalize(mut self) -> Result<[u8; SHA3_256_BYTES]> {
+        let mut digest = [0u8; SHA3_256_BYTES];
+        self.inner.finalize(&mut digest)?;
+        Ok(digest)
+    }
+}
+
+impl AsyncWrite for Sha3Writer {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(
+            self.get_mut()
+                .inner
+                .update(buf)
+                .map(|()| buf.len())
+                .map_err(io::Error::other),
+        )
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! SHA3-256 over a stream of bit chunks of any length
+
+use anyhow::Result;
+use bitvec::{order::Lsb0, slice::BitSlice, vec::BitVec};
+
+use crate::{Hasher, HasherBits, SHA3_256_BYTES, Sha3_256, Sha3Error, constants::SHA3_256_RATE};
+
+/// A SHA3-256 hasher fed by a bit-level stream, such as the output of a decompressor
+/// whose chunks may end part way through a byte.
+///
+/// Chunks given to [`push_bits`](Self::push_bits) may be any number of bits long.  They
+/// are buffered until at least one complete 1088 bit rate block is available, and only
+/// whole blocks are absorbed; the sub-block remainder, including any dangling partial
+/// byte, is carried over to the next chunk and flushed by [`finalize`](Self::finalize).
+/// The digest is that of the concatenation of every chunk pushed.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{BitStreamHasher, Lsb0, bits, sha3_256};
+/// # pub fn main() -> Result<()> {
+/// let mut hasher = BitStreamHasher::new();
+/// // "ab" split after 3 bits and after 11 bits
+/// hasher.push_bits(bits![u8, Lsb0; 1, 0, 0])?;
+/// hasher.push_bits(bits![u8, Lsb0; 0, 0, 1, 1, 0, 0, 1, 0])?;
+/// hasher.push_bits(bits![u8, Lsb0; 0, 0, 1, 1, 0])?;
+/// let mut digest = [0u8; 32];
+/// hasher.finalize(&mut digest)?;
+/// assert_eq!(sha3_256(b"ab")?, digest);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BitStreamHasher {
+    inner: Sha3_256,
+    // bits pushed but not yet absorbed, always shorter than the rate between calls
+    pending: BitVec<u8, Lsb0>,
+    finalized: bool,
+}
+
+impl BitStreamHasher {
+    /// Create a new bit stream hasher.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: Sha3_256::new(),
+            pending: BitVec::new(),
+            finalized: false,
+        }
+    }
+
+    /// The number of pushed bits that are buffered rather than absorbed.
+    #[must_use]
+    pub fn pending_bits(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Push the next chunk of the stream, absorbing any complete rate blocks.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    pub fn push_bits(&mut self, bits: &BitSlice<u8, Lsb0>) -> Result<()> {
+        if self.finalized {
+            return Err(Sha3Error::Finalized.into());
+        }
+        self.pending.extend_from_bitslice(bits);
+        let full = self.pending.len() - self.pending.len() % SHA3_256_RATE;
+        if full > 0 {
+            self.inner.update_bits(&self.pending[..full])?;
+            self.pending.drain(..full).for_each(drop);
+        }
+        Ok(())
+    }
+
+    /// Flush the buffered remainder and finalize the hash computation.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    pub fn finalize(&mut self, output: &mut [u8; SHA3_256_BYTES]) -> Result<()> {
+        self.inner.update_bits(&std::mem::take(&mut self.pending))?;
+        self.finalized = true;
+        self.inner.finalize(output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use bitvec::{order::Lsb0, vec::BitVec};
+
+    use super::BitStreamHasher;
+    use crate::{Hasher, HasherBits, SHA3_256_BYTES, Sha3_256, constants::SHA3_256_RATE};
+
+    #[test]
+    fn test_push_bits_in_chunks_of_3() -> Result<()> {
+        let bytes = (0..=255).cycle().take(400).collect::<Vec<u8>>();
+        // 3199 bits, not a multiple of 3, 8, or the rate
+        let bits = BitVec::<u8, Lsb0>::from_slice(&bytes)[..3199].to_bitvec();
+
+        let mut expected = [0u8; SHA3_256_BYTES];
+        let mut hasher = Sha3_256::new();
+        hasher.update_bits(&bits)?;
+        hasher.finalize(&mut expected)?;
+
+        let mut stream = BitStreamHasher::new();
+        for chunk in bits.chunks(3) {
+            stream.push_bits(chunk)?;
+            assert!(stream.pending_bits() < SHA3_256_RATE);
+        }
+        assert_eq!(3199 % SHA3_256_RATE, stream.pending_bits());
+        let mut result = [0u8; SHA3_256_BYTES];
+        stream.finalize(&mut result)?;
+        assert_eq!(expected, result);
+        assert!(stream.push_bits(&bits[..3]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Bloom filter bucket indices derived from SHAKE128
+
+use anyhow::Result;
+
+use crate::{Sha3Error, Shake128, XofHasher};
+
+/// Derive `k` Bloom filter bucket indices in `[0, m)` for `data`.
+///
+/// Each index is drawn from the SHAKE128 output for `data`: 8 bytes are read as a
+/// little-endian `u64` and reduced modulo `m`.  To avoid modulo bias, a draw at or above
+/// the largest multiple of `m` that fits in a `u64` is rejected and the next 8 bytes are
+/// used instead, so every index is uniformly distributed.  The same `data`, `k`, and `m`
+/// always give the same indices, in the same order.  Indices may repeat, as in any Bloom
+/// filter.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::bloom_indices;
+/// # pub fn main() -> Result<()> {
+/// let indices = bloom_indices(b"apple", 7, 1 << 20)?;
+/// assert_eq!(7, indices.len());
+/// assert!(indices.iter().all(|&index| index < 1 << 20));
+/// assert_eq!(indices, bloom_indices(b"apple", 7, 1 << 20)?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if `m` is zero, or if the underlying SHAKE128 operations fail.
+///
+pub fn bloom_indices(data: &[u8], k: usize, m: usize) -> Result<Vec<usize>> {
+    if m == 0 {
+        return Err(Sha3Error::InvalidBucketCount(m).into());
+    }
+    let mut xof = Shake128::new();
+    xof.update(data)?;
+    xof.finalize()?;
+    let modulus = m as u64;
+    // 2^64 mod m, the number of draws at the top of the range that would bias the result
+    let excess = (u64::MAX % modulus + 1) % modulus;
+    let max_draw = u64::MAX - excess;
+
+    let mut indices = Vec::with_capacity(k);
+    while indices.len() < k {
+        let mut bytes = [0u8; 8];
+        xof.get_bytes(&mut bytes, 8)?;
+        let draw = u64::from_le_bytes(bytes);
+        if draw <= max_draw {
+            indices.push(usize::try_from(draw % modulus)?);
+        }
+    }
+    Ok(indices)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::bloom_indices;
+    use crate::{Shake128, XofHasher};
+
+    fn draws(data: &[u8], count: usize) -> Result<Vec<u64>> {
+        let mut xof = Shake128::new();
+        xof.update(data)?;
+        Ok(xof
+            .take(count * 8)
+            .collect::<Vec<u8>>()
+            .chunks_exact(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, &b| (acc << 8) | u64::from(b))
+            })
+            .collect())
+    }
+
+    #[test]
+    fn test_bloom_indices_are_stable_and_in_range() -> Result<()> {
+        let indices = bloom_indices(b"bloom", 10, 1000)?;
+        assert_eq!(10, indices.len());
+        assert!(indices.iter().all(|&index| index < 1000));
+        assert_eq!(indices, bloom_indices(b"bloom", 10, 1000)?);
+        assert_ne!(indices, bloom_indices(b"blossom", 10, 1000)?);
+        // A prefix of the indices does not depend on k
+        assert_eq!(indices[..4], bloom_indices(b"bloom", 4, 1000)?);
+
+        // Rejection is vanishingly rare for a small m, so the draws map directly
+        let expected = draws(b"bloom", 10)?
+            .into_iter()
+            .map(|draw| usize::try_from(draw % 1000))
+            .collect::<Result<Vec<usize>, _>>()?;
+        assert_eq!(expected, indices);
+
+        assert!(bloom_indices(b"bloom", 10, 0).is_err());
+        assert!(bloom_indices(b"bloom", 0, 0).is_err());
+        assert!(bloom_indices(b"bloom", 0, 1000)?.is_empty());
+        Ok(())
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_bloom_indices_reject_biased_draws() -> Result<()> {
+        // With m = 2^63 + 1 only one multiple of m fits in a u64, so every draw of m or
+        // more (about half of them) must be rejected rather than reduced
+        let m = (1usize << 63) + 1;
+        let indices = bloom_indices(b"bloom", 16, m)?;
+        let accepted = draws(b"bloom", 64)?
+            .into_iter()
+            .filter(|&draw| draw < m as u64)
+            .take(16)
+            .map(usize::try_from)
+            .collect::<Result<Vec<usize>, _>>()?;
+        assert_eq!(accepted, indices);
+        assert!(draws(b"bloom", 16)?.iter().any(|&draw| draw >= m as u64));
+        Ok(())
+    }
+}
@@ -0,0 +1,427 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! A builder for custom Keccak sponge constructions
+
+use anyhow::Result;
+use bitvec::{bits, order::Lsb0, slice::BitSlice, vec::BitVec};
+
+use crate::{
+    SHA3_256_BYTES, Sha3Error, XofHasher, XofHasherBits,
+    constants::{
+        KECCAK_F_1600_ROUNDS, SHA3_224_CAPACITY, SHA3_224_RATE, SHA3_256_CAPACITY, SHA3_256_RATE,
+        SHA3_384_CAPACITY, SHA3_384_RATE, SHA3_512_CAPACITY, SHA3_512_RATE, SHA3_WIDTH,
+        SHAKE_128_CAPACITY, SHAKE_128_RATE,
+    },
+    sponge::Keccak1600Sponge,
+    traits::Sponge,
+};
+
+/// A builder for a [`KeccakHasher`] with custom sponge parameters.
+///
+/// The rate and capacity are given in **bits** and must sum to 1600.  The builder starts
+/// from the parameters of `KECCAK[512]` (a rate of 1088 bits, 24 rounds, and no domain
+/// separation bits), so each standard construction only differs in a few settings:
+///
+/// | construction | rate | capacity | rounds | suffix |
+/// |--------------|------|----------|--------|--------|
+/// | SHA3-256     | 1088 | 512      | 24     | `01`   |
+/// | SHAKE128     | 1344 | 256      | 24     | `1111` |
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{KeccakBuilder, Lsb0, XofHasher, bits};
+/// # pub fn main() -> Result<()> {
+/// // SHA3-256 through the builder
+/// let mut hasher = KeccakBuilder::new()
+///     .rate(1088)
+///     .capacity(512)
+///     .suffix(bits![u8, Lsb0; 0, 1])
+///     .build()?;
+/// let mut digest = [0u8; 32];
+/// hasher.update(b"abc")?;
+/// hasher.finalize()?;
+/// hasher.get_bytes(&mut digest, 32)?;
+/// assert_eq!(0x3a, digest[0]);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeccakBuilder {
+    rate: usize,
+    capacity: usize,
+    rounds: usize,
+    suffix: BitVec<u8, Lsb0>,
+    max_input: Option<usize>,
+}
+
+impl Default for KeccakBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeccakBuilder {
+    /// Create a new builder with the `KECCAK[512]` parameters.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rate: SHA3_256_RATE,
+            capacity: SHA3_256_CAPACITY,
+            rounds: KECCAK_F_1600_ROUNDS,
+            suffix: BitVec::new(),
+            max_input: None,
+        }
+    }
+
+    /// Set the rate of the sponge in bits.
+    #[must_use]
+    pub fn rate(self, rate: usize) -> Self {
+        Self { rate, ..self }
+    }
+
+    /// Set the capacity of the sponge in bits.
+    #[must_use]
+    pub fn capacity(self, capacity: usize) -> Self {
+        Self { capacity, ..self }
+    }
+
+    /// Set the number of Keccak-p\[1600\] rounds per permutation (at most 24).
+    ///
+    /// As with Keccak-p, a reduced round count uses the last `rounds` rounds of
+    /// Keccak-f\[1600\].
+    #[must_use]
+    pub fn rounds(self, rounds: usize) -> Self {
+        Self { rounds, ..self }
+    }
+
+    /// Set the domain separation bits appended to the message before `pad10*1`.
+    #[must_use]
+    pub fn suffix(self, suffix: &BitSlice<u8, Lsb0>) -> Self {
+        Self {
+            suffix: suffix.to_bitvec(),
+            ..self
+        }
+    }
+
+    /// Cap the total input absorbed by the hasher at `max_input` bytes.
+    ///
+    /// Once set, an update that would take the cumulative input past the cap is rejected
+    /// with [`Sha3Error::InputTooLarge`] and absorbs nothing.  By default the input is
+    /// unbounded.
+    #[must_use]
+    pub fn max_input(self, max_input: usize) -> Self {
+        Self {
+            max_input: Some(max_input),
+            ..self
+        }
+    }
+
+    /// Build the hasher.
+    ///
+    /// # Errors
+    /// An error will be returned if the rate and capacity do not sum to 1600, if the rate
+    /// is zero, or if more than 24 rounds were requested.
+    ///
+    pub fn build(self) -> Result<KeccakHasher> {
+        if self.rate.checked_add(self.capacity) != Some(SHA3_WIDTH) {
+            Err(Sha3Error::InvalidSpongeWidth(self.rate, self.capacity).into())
+        } else if self.rate == 0 {
+            Err(Sha3Error::InvalidRate(self.rate).into())
+        } else if self.rounds > KECCAK_F_1600_ROUNDS {
+            Err(Sha3Error::InvalidRoundCount(self.rounds).into())
+        } else {
+            Ok(KeccakHasher {
+                sponge: Keccak1600Sponge::new(self.rate, self.capacity).with_rounds(self.rounds),
+                suffix: self.suffix,
+                max_input: self.max_input,
+                absorbed_bits: 0,
+            })
+        }
+    }
+}
+
+// The standard (rate, capacity) pairs in bits, see `identify_params`
+const STANDARD_PARAMS: [(usize, usize, &str); 5] = [
+    (SHA3_224_RATE, SHA3_224_CAPACITY, "SHA3-224"),
+    (SHA3_256_RATE, SHA3_256_CAPACITY, "SHA3-256"),
+    (SHA3_384_RATE, SHA3_384_CAPACITY, "SHA3-384"),
+    (SHA3_512_RATE, SHA3_512_CAPACITY, "SHA3-512"),
+    (SHAKE_128_RATE, SHAKE_128_CAPACITY, "SHAKE128"),
+];
+
+/// Name the standard construction that uses the sponge parameters `rate` and `capacity`
+/// (in bits), or `None` if they are not the parameters of a FIPS 202 function.
+///
+/// This is a diagnostic for custom sponges built with [`KeccakBuilder`].  Only the rate
+/// and capacity are compared, not the domain separation suffix, so functions that differ
+/// only in their suffix share a name: SHAKE256 (and cSHAKE256) use the same parameters as
+/// SHA3-256 and are reported as `"SHA3-256"`.
+///
+/// ```
+/// # use shashasha::identify_params;
+/// assert_eq!(Some("SHA3-512"), identify_params(576, 1024));
+/// assert_eq!(None, identify_params(1000, 600));
+/// ```
+#[must_use]
+pub fn identify_params(rate: usize, capacity: usize) -> Option<&'static str> {
+    STANDARD_PARAMS
+        .iter()
+        .find(|(r, c, _)| *r == rate && *c == capacity)
+        .map(|(_, _, name)| *name)
+}
+
+/// A fast 256-bit hash of `data`: SHA3-256 with `rounds` rounds of Keccak-p\[1600\] per
+/// permutation instead of 24.
+///
+/// # Security
+/// **This is NOT cryptographically secure for `rounds < 24`.**  Reduced-round Keccak has
+/// practical collision and preimage attacks at low round counts, so only use it where an
+/// adversary cannot choose the input, e.g. for in-memory deduplication keys.  With 24
+/// rounds the result is exactly [`sha3_256`](crate::sha3_256).
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{fast_hash_256, sha3_256};
+/// # pub fn main() -> Result<()> {
+/// assert_eq!(sha3_256(b"abc")?, fast_hash_256(b"abc", 24)?);
+/// assert_ne!(fast_hash_256(b"abc", 12)?, fast_hash_256(b"abc", 24)?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if more than 24 rounds are requested.
+///
+pub fn fast_hash_256(data: &[u8], rounds: usize) -> Result<[u8; SHA3_256_BYTES]> {
+    let mut hasher = KeccakBuilder::new()
+        .rounds(rounds)
+        .suffix(bits![u8, Lsb0; 0, 1])
+        .build()?;
+    let mut digest = [0u8; SHA3_256_BYTES];
+    hasher.update(data)?;
+    hasher.finalize()?;
+    hasher.get_bytes(&mut digest, SHA3_256_BYTES)?;
+    Ok(digest)
+}
+
+/// A Keccak sponge hasher with the parameters chosen through a [`KeccakBuilder`].
+///
+/// The hasher is used like an XOF: finalize it, then squeeze as many bytes or bits as
+/// needed.  A fixed-length hash is the first `d` bits of that output.
+#[derive(Clone, Debug)]
+pub struct KeccakHasher {
+    sponge: Keccak1600Sponge,
+    suffix: BitVec<u8, Lsb0>,
+    max_input: Option<usize>,
+    absorbed_bits: usize,
+}
+
+impl KeccakHasher {
+    // Count `num_bits` more input bits against the configured input cap
+    fn check_input(&mut self, num_bits: usize) -> Result<()> {
+        let absorbed_bits = self.absorbed_bits.saturating_add(num_bits);
+        match self.max_input {
+            Some(max_input) if absorbed_bits > max_input.saturating_mul(8) => {
+                Err(Sha3Error::InputTooLarge(max_input).into())
+            }
+            _ => {
+                self.absorbed_bits = absorbed_bits;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl XofHasher for KeccakHasher {
+    fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.check_input(data.len().saturating_mul(8))?;
+        self.sponge.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        if self.sponge.finalized() {
+            Err(Sha3Error::Finalized.into())
+        } else {
+            self.sponge.append_suffix(&self.suffix)?;
+            self.sponge.absorb()
+        }
+    }
+
+    fn get_bytes(&mut self, output: &mut [u8], num_bytes: usize) -> Result<()> {
+        let num_bits = num_bytes
+            .checked_mul(8)
+            .ok_or(Sha3Error::OutputLengthMismatch(output.len(), num_bytes))?;
+        self.sponge.squeeze(output, num_bits)
+    }
+}
+
+impl XofHasherBits for KeccakHasher {
+    fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()> {
+        self.check_input(data.len())?;
+        self.sponge.update_bits(data)
+    }
+
+    fn get_bits(&mut self, output: &mut BitVec<u8, Lsb0>, num_bits: usize) -> Result<()> {
+        self.sponge.squeeze_b(output, num_bits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use bitvec::{bits, order::Lsb0};
+
+    use super::{KeccakBuilder, fast_hash_256, identify_params};
+    use crate::{Shake128, XofHasher, XofHasherBits, nist_format, sha3_256};
+
+    #[test]
+    fn test_builder_sha3_256_empty() -> Result<()> {
+        let mut hasher = KeccakBuilder::new()
+            .rate(1088)
+            .capacity(512)
+            .rounds(24)
+            .suffix(bits![u8, Lsb0; 0, 1])
+            .build()?;
+        let mut digest = [0u8; 32];
+        hasher.finalize()?;
+        hasher.get_bytes(&mut digest, 32)?;
+        assert_eq!(
+            "A7 FF C6 F8 BF 1E D7 66 51 C1 47 56 A0 61 D6 62 F5 80 FF 4D E4 3B 49 FA 82 D8 0A 4B 80 F8 43 4A",
+            nist_format(&digest)
+        );
+        assert!(hasher.finalize().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder_shake128() -> Result<()> {
+        let mut hasher = KeccakBuilder::new()
+            .rate(1344)
+            .capacity(256)
+            .suffix(bits![u8, Lsb0; 1, 1, 1, 1])
+            .build()?;
+        hasher.update(b"Hello, world!")?;
+        hasher.finalize()?;
+        let mut output = [0u8; 200];
+        hasher.get_bytes(&mut output, 200)?;
+
+        let mut expected = Shake128::new();
+        expected.update(b"Hello, world!")?;
+        assert_eq!(expected.take(200).collect::<Vec<u8>>(), output);
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder_keccak_256_default() -> Result<()> {
+        let mut hasher = KeccakBuilder::default().build()?;
+        let mut digest = [0u8; 32];
+        hasher.finalize()?;
+        hasher.get_bytes(&mut digest, 32)?;
+        assert_eq!(0xc5, digest[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder_reduced_rounds_differ() -> Result<()> {
+        let mut full = KeccakBuilder::new().build()?;
+        let mut reduced = KeccakBuilder::new().rounds(12).build()?;
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        full.finalize()?;
+        reduced.finalize()?;
+        full.get_bytes(&mut a, 32)?;
+        reduced.get_bytes(&mut b, 32)?;
+        assert_ne!(a, b);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fast_hash_256_rounds() -> Result<()> {
+        let data = b"deduplication key";
+        assert_eq!(sha3_256(data)?, fast_hash_256(data, 24)?);
+        assert_eq!(fast_hash_256(data, 4)?, fast_hash_256(data, 4)?);
+        let digests = (1..=24)
+            .map(|rounds| fast_hash_256(data, rounds))
+            .collect::<Result<Vec<_>>>()?;
+        for (i, a) in digests.iter().enumerate() {
+            assert!(digests[i + 1..].iter().all(|b| a != b));
+        }
+        assert!(fast_hash_256(data, 25).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_identify_params() {
+        assert_eq!(Some("SHA3-224"), identify_params(1152, 448));
+        assert_eq!(Some("SHA3-256"), identify_params(1088, 512));
+        assert_eq!(Some("SHA3-384"), identify_params(832, 768));
+        assert_eq!(Some("SHA3-512"), identify_params(576, 1024));
+        assert_eq!(Some("SHAKE128"), identify_params(1344, 256));
+        assert_eq!(None, identify_params(512, 1088));
+        assert_eq!(None, identify_params(1340, 260));
+        assert_eq!(None, identify_params(0, 0));
+    }
+
+    #[test]
+    fn test_builder_invalid_parameters() {
+        assert!(
+            KeccakBuilder::new()
+                .rate(1088)
+                .capacity(256)
+                .build()
+                .is_err()
+        );
+        assert!(KeccakBuilder::new().rate(0).capacity(1600).build().is_err());
+        assert!(KeccakBuilder::new().rate(usize::MAX).build().is_err());
+        assert!(KeccakBuilder::new().rounds(25).build().is_err());
+    }
+
+    #[test]
+    fn test_builder_non_byte_aligned_capacity() -> Result<()> {
+        // A 260 bit capacity leaves a 1340 bit rate, which ends part way through a byte
+        // and a lane, so the capacity bits must be left exactly untouched
+        let mut hasher = KeccakBuilder::new()
+            .rate(1340)
+            .capacity(260)
+            .suffix(bits![u8, Lsb0; 1, 1, 1, 1])
+            .build()?;
+        hasher.update(&(0..200).collect::<Vec<u8>>())?;
+        hasher.finalize()?;
+        // Squeezing 300 bytes crosses a refill that is not byte aligned
+        let mut output = [0u8; 300];
+        hasher.get_bytes(&mut output, 300)?;
+        assert_eq!(
+            "EF 8D 99 88 AD EE A7 13 B1 81 C4 72 77 FA 97 17",
+            nist_format(&output[..16])
+        );
+        assert_eq!("47 E2 86 62 F5 B2 F4 5C B3", nist_format(&output[291..]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder_max_input() -> Result<()> {
+        let mut hasher = KeccakBuilder::new().max_input(10).build()?;
+        hasher.update(b"Hello")?;
+        assert!(hasher.update(b"world!").is_err());
+        // The rejected update absorbed nothing, so the cap still has room
+        hasher.update(b"world")?;
+        assert!(hasher.update_bits(bits![u8, Lsb0; 1]).is_err());
+        hasher.finalize()?;
+        let mut output = [0u8; 32];
+        hasher.get_bytes(&mut output, 32)?;
+
+        let mut expected = KeccakBuilder::new().build()?;
+        expected.update(b"Helloworld")?;
+        expected.finalize()?;
+        let mut digest = [0u8; 32];
+        expected.get_bytes(&mut digest, 32)?;
+        assert_eq!(digest, output);
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! SHA3-256 digests of serializable values in a canonical encoding
+
+use anyhow::Result;
+use ciborium::Value;
+use serde::Serialize;
+
+use crate::{SHA3_256_BYTES, sha3_256};
+
+/// Compute the SHA3-256 digest of the canonical CBOR encoding of `value`.
+///
+/// `value` is serialized to CBOR (RFC 8949) and the entries of every map, at any depth,
+/// are sorted by the bytewise order of their encoded keys, as in the deterministic
+/// encoding of RFC 8949 §4.2.1.  Values that serialize to the same entries therefore hash
+/// the same regardless of the iteration order of the map, e.g. two `HashMap`s with the
+/// same contents.  Sequences keep their order, and struct fields are encoded as map
+/// entries keyed by field name.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::hash_canonical;
+/// # use std::collections::{BTreeMap, HashMap};
+/// # pub fn main() -> Result<()> {
+/// let hashed = HashMap::from([("b", 2), ("a", 1)]);
+/// let sorted = BTreeMap::from([("a", 1), ("b", 2)]);
+/// assert_eq!(hash_canonical(&hashed)?, hash_canonical(&sorted)?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if `value` cannot be serialized, or if the hash fails.
+///
+pub fn hash_canonical<T: Serialize>(value: &T) -> Result<[u8; SHA3_256_BYTES]> {
+    let mut value = Value::serialized(value)?;
+    canonicalize(&mut value)?;
+    let mut encoded = Vec::new();
+    ciborium::into_writer(&value, &mut encoded)?;
+    sha3_256(&encoded)
+}
+
+// Sort the entries of every map by their encoded keys
+fn canonicalize(value: &mut Value) -> Result<()> {
+    if let Some(values) = value.as_array_mut() {
+        for value in values {
+            canonicalize(value)?;
+        }
+    } else if let Some(entries) = value.as_map_mut() {
+        let mut keyed = std::mem::take(entries)
+            .into_iter()
+            .map(|(mut key, mut value)| {
+                canonicalize(&mut key)?;
+                canonicalize(&mut value)?;
+                let mut encoded_key = Vec::new();
+                ciborium::into_writer(&key, &mut encoded_key)?;
+                Ok((encoded_key, (key, value)))
+            })
+            .collect::<Result<Vec<(Vec<u8>, (Value, Value))>>>()?;
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        *entries = keyed.into_iter().map(|(_, entry)| entry).collect();
+    } else if let Some((_, value)) = value.as_tag_mut() {
+        canonicalize(value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, HashMap};
+
+    use anyhow::Result;
+
+    use super::hash_canonical;
+
+    #[test]
+    fn test_hash_canonical_ignores_insertion_order() -> Result<()> {
+        let mut forward = HashMap::new();
+        let mut backward = HashMap::new();
+        for i in 0..50u32 {
+            let _ = forward.insert(format!("key {i}"), i);
+            let _ = backward.insert(format!("key {}", 49 - i), 49 - i);
+        }
+        assert_eq!(hash_canonical(&forward)?, hash_canonical(&backward)?);
+
+        let sorted = forward
+            .clone()
+            .into_iter()
+            .collect::<BTreeMap<String, u32>>();
+        assert_eq!(hash_canonical(&forward)?, hash_canonical(&sorted)?);
+
+        let _ = backward.insert("key 0".to_string(), 1);
+        assert_ne!(hash_canonical(&forward)?, hash_canonical(&backward)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_canonical_nested() -> Result<()> {
+        let inner_a = HashMap::from([(1u8, "one"), (2, "two"), (3, "three")]);
+        let inner_b = HashMap::from([(3u8, "three"), (1, "one"), (2, "two")]);
+        let outer_a = vec![HashMap::from([("inner", inner_a.clone())])];
+        let outer_b = vec![HashMap::from([("inner", inner_b)])];
+        assert_eq!(hash_canonical(&outer_a)?, hash_canonical(&outer_b)?);
+        // Sequences keep their order
+        assert_ne!(hash_canonical(&[1, 2])?, hash_canonical(&[2, 1])?);
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! SHA3-256 digests of a stream at intermediate checkpoints
+
+use anyhow::Result;
+
+use crate::{Hasher, SHA3_256_BYTES, Sha3_256, Sha3Error};
+
+/// A SHA3-256 hasher that can report the digest of the data absorbed so far without
+/// ending the hash.
+///
+/// Each [`checkpoint`](Self::checkpoint) finalizes a clone of the running hasher, so the
+/// main hash stays live and later updates continue from where they left off.  This gives
+/// periodic integrity checkpoints (e.g. a digest-so-far every 1 MiB) in a single pass over
+/// the stream.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{CheckpointHasher, sha3_256};
+/// # pub fn main() -> Result<()> {
+/// let mut hasher = CheckpointHasher::new();
+/// hasher.update(b"Hello, ")?;
+/// assert_eq!(sha3_256(b"Hello, ")?, hasher.checkpoint()?);
+/// hasher.update(b"world!")?;
+/// assert_eq!(sha3_256(b"Hello, world!")?, hasher.checkpoint()?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// A hasher created with [`with_integrity_checkpoints`](Self::with_integrity_checkpoints)
+/// also records the SHA3-256 digest of every fixed-size segment of the stream, for
+/// comparison against a manifest when hashing large files resumably.
+#[derive(Clone, Debug)]
+pub struct CheckpointHasher {
+    inner: Sha3_256,
+    // the segment size for integrity checkpoints, if enabled
+    every: Option<usize>,
+    segment: Sha3_256,
+    segment_len: usize,
+    absorbed: usize,
+    checkpoints: Vec<(usize, [u8; SHA3_256_BYTES])>,
+}
+
+impl Default for CheckpointHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CheckpointHasher {
+    /// Create a new checkpoint hasher.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: Sha3_256::new(),
+            every: None,
+            segment: Sha3_256::new(),
+            segment_len: 0,
+            absorbed: 0,
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// Create a new checkpoint hasher that records an integrity checkpoint for every
+    /// `every` bytes absorbed.
+    ///
+    /// Each checkpoint is `(offset, digest)`, where `offset` is the position in the stream
+    /// of the first byte of the segment and `digest` is the SHA3-256 digest of the `every`
+    /// bytes of the segment alone.  A trailing segment shorter than `every` bytes is not
+    /// recorded.
+    ///
+    /// # Errors
+    /// An error will be returned if `every` is zero.
+    ///
+    pub fn with_integrity_checkpoints(every: usize) -> Result<Self> {
+        if every == 0 {
+            Err(Sha3Error::InvalidChunkSize(every).into())
+        } else {
+            Ok(Self {
+                every: Some(every),
+                ..Self::new()
+            })
+        }
+    }
+
+    /// The integrity checkpoints recorded so far, in stream order.
+    ///
+    /// This is empty unless the hasher was created with
+    /// [`with_integrity_checkpoints`](Self::with_integrity_checkpoints).
+    #[must_use]
+    pub fn checkpoints(&self) -> &[(usize, [u8; SHA3_256_BYTES])] {
+        &self.checkpoints
+    }
+
+    /// Update the running hash with new byte data.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying SHA3-256 update fails.
+    ///
+    pub fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.inner.update(data)?;
+        if let Some(every) = self.every {
+            let mut rest = data;
+            while !rest.is_empty() {
+                let (head, tail) = rest.split_at(rest.len().min(every - self.segment_len));
+                self.segment.update(head)?;
+                self.segment_len += head.len();
+                self.absorbed += head.len();
+                if self.segment_len == every {
+                    let mut digest = [0u8; SHA3_256_BYTES];
+                    std::mem::take(&mut self.segment).finalize(&mut digest)?;
+                    self.checkpoints.push((self.absorbed - every, digest));
+                    self.segment_len = 0;
+                }
+                rest = tail;
+            }
+        }
+        Ok(())
+    }
+
+    /// Compute the SHA3-256 digest of all of the data absorbed so far.
+    ///
+    /// The running hash is not finalized, so further updates may follow.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying SHA3-256 finalization fails.
+    ///
+    pub fn checkpoint(&mut self) -> Result<[u8; SHA3_256_BYTES]> {
+        let mut digest = [0u8; SHA3_256_BYTES];
+        self.inner.clone().finalize(&mut digest)?;
+        Ok(digest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::CheckpointHasher;
+    use crate::sha3_256;
+
+    #[test]
+    fn test_checkpoints_match_data_so_far() -> Result<()> {
+        let data = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+        let mut hasher = CheckpointHasher::new();
+        let segments = [&data[..100], &data[100..137], &data[137..]];
+        let mut end = 0;
+        for segment in segments {
+            hasher.update(segment)?;
+            end += segment.len();
+            assert_eq!(sha3_256(&data[..end])?, hasher.checkpoint()?);
+        }
+        // Repeated checkpoints do not disturb the running hash
+        assert_eq!(hasher.checkpoint()?, hasher.checkpoint()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_integrity_checkpoints_match_segments() -> Result<()> {
+        let data = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+        let mut hasher = CheckpointHasher::with_integrity_checkpoints(300)?;
+        // Updates that straddle the segment boundaries
+        for chunk in data.chunks(170) {
+            hasher.update(chunk)?;
+        }
+
+        let expected = data
+            .chunks_exact(300)
+            .enumerate()
+            .map(|(i, segment)| Ok((i * 300, sha3_256(segment)?)))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(3, expected.len());
+        assert_eq!(expected.as_slice(), hasher.checkpoints());
+        // The running hash covers the whole stream, including the trailing 100 bytes
+        assert_eq!(sha3_256(&data)?, hasher.checkpoint()?);
+
+        assert!(CheckpointHasher::new().checkpoints().is_empty());
+        assert!(CheckpointHasher::with_integrity_checkpoints(0).is_err());
+        Ok(())
+    }
+}
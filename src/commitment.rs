@@ -0,0 +1,111 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Commit/reveal commitments built on SHAKE256
+
+use anyhow::Result;
+
+use crate::{Shake256, XofHasher, utils::ct_eq};
+
+/// The size of a commitment and of its opening in bytes
+pub const COMMITMENT_BYTES: usize = 32;
+
+/// A SHAKE256 commitment scheme.
+///
+/// A commitment to `value` is `SHAKE256(value || opening, 256)`, where the 32 byte
+/// `opening` is `SHAKE256(seed, 256)`.  Revealing `value` and `opening` lets anyone check
+/// the commitment with [`open`](Self::open).
+///
+/// Supplying the randomness is the caller's responsibility.  The commitment only hides
+/// `value` if `seed` is secret and has at least 256 bits of entropy (e.g. 32 bytes from
+/// an operating system RNG).  A fixed seed gives deterministic commitments, which is
+/// useful in tests but hides nothing.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Shake256Commitment;
+
+impl Shake256Commitment {
+    /// Commit to `value`, deriving the opening from `seed`.
+    ///
+    /// Returns the `(commitment, opening)` pair.  Publish the commitment, and keep the
+    /// opening secret until `value` is revealed.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying SHAKE256 operations fail.
+    ///
+    pub fn commit(
+        value: &[u8],
+        seed: &[u8],
+    ) -> Result<([u8; COMMITMENT_BYTES], [u8; COMMITMENT_BYTES])> {
+        let mut opening = [0u8; COMMITMENT_BYTES];
+        let mut hasher = Shake256::new();
+        hasher.update(seed)?;
+        hasher.finalize()?;
+        hasher.get_bytes(&mut opening, COMMITMENT_BYTES)?;
+        Ok((Self::commitment(value, &opening)?, opening))
+    }
+
+    /// Check that `commitment` is a commitment to `value` with the given `opening`.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying SHAKE256 operations fail.
+    ///
+    pub fn open(
+        commitment: &[u8; COMMITMENT_BYTES],
+        value: &[u8],
+        opening: &[u8; COMMITMENT_BYTES],
+    ) -> Result<bool> {
+        Ok(ct_eq(&Self::commitment(value, opening)?, commitment))
+    }
+
+    fn commitment(
+        value: &[u8],
+        opening: &[u8; COMMITMENT_BYTES],
+    ) -> Result<[u8; COMMITMENT_BYTES]> {
+        let mut commitment = [0u8; COMMITMENT_BYTES];
+        let mut hasher = Shake256::new();
+        hasher.update(value)?;
+        hasher.update(opening)?;
+        hasher.finalize()?;
+        hasher.get_bytes(&mut commitment, COMMITMENT_BYTES)?;
+        Ok(commitment)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::Shake256Commitment;
+
+    #[test]
+    fn test_commitment_open() -> Result<()> {
+        let (commitment, opening) = Shake256Commitment::commit(b"heads", b"test seed")?;
+        assert!(Shake256Commitment::open(&commitment, b"heads", &opening)?);
+        assert!(!Shake256Commitment::open(&commitment, b"tails", &opening)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_commitment_wrong_opening() -> Result<()> {
+        let (commitment, mut opening) = Shake256Commitment::commit(b"heads", b"test seed")?;
+        opening[0] ^= 0x01;
+        assert!(!Shake256Commitment::open(&commitment, b"heads", &opening)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_commitment_is_deterministic_for_a_seed() -> Result<()> {
+        let first = Shake256Commitment::commit(b"heads", b"test seed")?;
+        let second = Shake256Commitment::commit(b"heads", b"test seed")?;
+        let other = Shake256Commitment::commit(b"heads", b"other seed")?;
+        assert_eq!(first, second);
+        assert_ne!(first.0, other.0);
+        assert_ne!(first.1, other.1);
+        Ok(())
+    }
+}
@@ -7,6 +7,8 @@
 // modified, or distributed except according to those terms.
 
 pub(crate) const SHA3_WIDTH: usize = 1600;
+/// The number of rounds in Keccak-f\[1600\]
+pub(crate) const KECCAK_F_1600_ROUNDS: usize = 24;
 
 // SHA-224 constants
 pub(crate) const SHA3_224_BITS: usize = 224;
@@ -44,6 +46,9 @@ pub(crate) const SHAKE_128_RATE: usize = SHA3_WIDTH - SHAKE_128_CAPACITY;
 pub(crate) const SHAKE_256_CAPACITY: usize = 512;
 pub(crate) const SHAKE_256_RATE: usize = SHA3_WIDTH - SHAKE_256_CAPACITY;
 
+/// The minimum tag length in bytes accepted by `finalize_tag` (128 bits of security)
+pub const MIN_TAG_BYTES: usize = 16;
+
 /// The number of lanes in the state array used by the keccak function
 pub const LANE_COUNT: usize = 25;
 pub(crate) const RHO: [u32; 24] = [
@@ -78,3 +83,48 @@ pub(crate) const ROUND_CONSTS: [u64; 24] = [
     0x0_000_000_080_000_001,
     0x8_000_000_080_008_008,
 ];
+
+#[cfg(test)]
+mod test {
+    use super::{PI, RHO, ROUND_CONSTS};
+
+    // rc(t) from FIPS 202 Algorithm 5, the output bit of an 8-bit LFSR
+    fn rc(t: usize) -> bool {
+        let mut r = 1u8;
+        for _ in 0..t % 255 {
+            let overflow = r & 0x80 != 0;
+            r <<= 1;
+            if overflow {
+                r ^= 0x71;
+            }
+        }
+        r & 1 == 1
+    }
+
+    #[test]
+    fn round_consts_match_lfsr() {
+        for (round, expected) in ROUND_CONSTS.iter().enumerate() {
+            // FIPS 202 Algorithm 6: RC[2^j - 1] = rc(j + 7 * round)
+            let round_const = (0..7)
+                .filter(|j| rc(j + 7 * round))
+                .fold(0u64, |acc, j| acc | (1 << ((1 << j) - 1)));
+            assert_eq!(*expected, round_const, "round {round}");
+        }
+    }
+
+    #[test]
+    fn rho_and_pi_match_lane_walk() {
+        // FIPS 202 Algorithms 2 and 3 combined: starting from lane (1, 0), each step moves
+        // to (y, 2x + 3y) and rotates by the next triangular number.
+        let (mut x, mut y) = (1, 0);
+        for t in 0..24 {
+            (x, y) = (y, (2 * x + 3 * y) % 5);
+            assert_eq!(5 * y + x, PI[t], "pi step {t}");
+            assert_eq!(
+                u32::try_from((t + 1) * (t + 2) / 2 % 64),
+                Ok(RHO[t]),
+                "rho step {t}"
+            );
+        }
+    }
+}
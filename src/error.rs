@@ -23,4 +23,67 @@ pub enum Sha3Error {
     /// Thrown if an update is requested after finalize has been called.
     #[error("Hasher has been finalized; no further updates allowed")]
     Finalized,
+    /// Thrown if a raw block does not match the rate of the sponge.
+    #[error("Block length {0} does not match the sponge rate {1}")]
+    InvalidBlockLength(usize, usize),
+    /// Thrown if a chunk size of zero is given to a chunked hashing function.
+    #[error("Invalid chunk size {0}")]
+    InvalidChunkSize(usize),
+    /// Thrown if a rolling window size of zero is requested.
+    #[error("Invalid window size {0}")]
+    InvalidWindowSize(usize),
+    /// Thrown if a sponge rate and capacity (in bytes) do not sum to the 200 byte state width.
+    #[error("Rate {0} and capacity {1} (in bytes) must sum to 200")]
+    InvalidSpongeParameters(usize, usize),
+    /// Thrown if a strict conversion is given a bit length that is not a multiple of 8.
+    #[error("Bit length {0} is not a multiple of 8")]
+    NonByteAligned(usize),
+    /// Thrown if a midstate is resumed by a hasher with a different rate.
+    #[error("Midstate rate {0} does not match the hasher rate {1}")]
+    MidstateMismatch(usize, usize),
+    /// Thrown if no SHAKE function provides the requested security level (in bits).
+    #[error("Unsupported security level {0}; at most 256 bits is supported")]
+    InvalidSecurityLevel(usize),
+    /// Thrown if a proof is requested for a leaf index outside the tree.
+    #[error("Leaf index {0} is out of range for a tree with {1} leaves")]
+    InvalidLeafIndex(usize, usize),
+    /// Thrown if a sponge rate and capacity (in bits) do not sum to the 1600 bit state width.
+    #[error("Rate {0} and capacity {1} (in bits) must sum to 1600")]
+    InvalidSpongeWidth(usize, usize),
+    /// Thrown if a tag shorter than the minimum tag length (in bytes) is requested.
+    #[error("Tag length {0} is below the minimum of {1} bytes")]
+    TagTooShort(usize, usize),
+    /// Thrown if more than 8 trailing bits are requested from a single trailing byte.
+    #[error("Invalid trailing bit count {0}; at most 8 bits fit in the trailing byte")]
+    InvalidTrailingBits(usize),
+    /// Thrown if a hex string contains a character that is not a hex digit.
+    #[error("Invalid hex digit '{0}'")]
+    InvalidHexDigit(char),
+    /// Thrown if a hex string has an odd number of hex digits.
+    #[error("Hex string has an odd number of digits ({0})")]
+    OddHexLength(usize),
+    /// Thrown if a bit string contains a character that is not `0` or `1`.
+    #[error("Invalid bit digit '{0}'")]
+    InvalidBitDigit(char),
+    /// Thrown if an update would take the absorbed input past the configured maximum (in bytes).
+    #[error("Input exceeds the maximum of {0} bytes")]
+    InputTooLarge(usize),
+    /// Thrown if a state bit coordinate `(x, y, z)` is outside the 5x5x64 Keccak-f\[1600\] state.
+    #[error("State coordinate ({0}, {1}, {2}) is out of range")]
+    InvalidStateCoordinate(usize, usize, usize),
+    /// Thrown if squeeze output is requested from a hasher that has not been finalized.
+    #[error("Hasher has not been finalized; the squeeze has not started")]
+    NotFinalized,
+    /// Thrown if a squeeze token has the wrong length or more buffered bits than the rate.
+    #[error("Invalid squeeze token")]
+    InvalidSqueezeToken,
+    /// Thrown if a sponge rate (in bits) is zero, or is wider than the 1600 bit state.
+    #[error("Invalid rate {0}; the rate must be between 1 and 1600 bits")]
+    InvalidRate(usize),
+    /// Thrown if a raw block is absorbed while a partial message block is still buffered.
+    #[error("Raw blocks must start on a block boundary; {0} message bits are buffered")]
+    UnalignedRawBlock(usize),
+    /// Thrown if a Bloom filter has no buckets to draw indices from.
+    #[error("Invalid bucket count {0}")]
+    InvalidBucketCount(usize),
 }
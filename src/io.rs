@@ -0,0 +1,345 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! `std::io` helpers for feeding hashers
+
+use std::{
+    fs::File,
+    io::{self, BufRead, Read},
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+use crate::{Hasher, SHA3_256_BYTES, Sha3_256, sp800_185::encoding::left_encode};
+
+// The size of the buffer used to read file contents
+const READ_BUFFER_BYTES: usize = 8192;
+
+/// Absorb the lines read from `reader` into `hasher`, terminating every line with a
+/// canonical `\n`.
+///
+/// Each line has its `\n` terminator removed and, if `normalize_crlf` is `true`, a single
+/// trailing `\r` as well, before the line and a `\n` are absorbed.  With normalization
+/// enabled `"a\r\nb\n"` and `"a\nb\n"` produce the same digest.  Because every line is
+/// terminated, a final line without a trailing newline hashes the same as one with it.
+/// Lines are handled as raw bytes, so the input is not required to be valid UTF-8.
+///
+/// # Errors
+/// An error will be returned if reading from `reader` fails, or if the hasher has already
+/// been finalized.
+///
+pub fn hash_lines<const D_BYTES: usize, H, R>(
+    hasher: &mut H,
+    mut reader: R,
+    normalize_crlf: bool,
+) -> io::Result<()>
+where
+    H: Hasher<D_BYTES>,
+    R: BufRead,
+{
+    let mut line = Vec::new();
+
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        if line.last() == Some(&b'\n') {
+            let _ = line.pop();
+        }
+        if normalize_crlf && line.last() == Some(&b'\r') {
+            let _ = line.pop();
+        }
+        line.push(b'\n');
+        hasher.update(&line).map_err(io::Error::other)?;
+        line.clear();
+    }
+    Ok(())
+}
+
+/// Compute a SHA3-256 fingerprint of the file at `path` from its size, optionally its
+/// modification time, and its contents.
+///
+/// The fingerprint is the SHA3-256 digest of:
+///
+/// * `left_encode(size)`, the file size in bytes in the SP 800-185 encoding, then
+/// * with `include_mtime`, the byte `0x01`, the whole seconds of the modification time
+///   since the UNIX epoch as a little-endian `u64`, and the sub-second nanoseconds as a
+///   little-endian `u32`; without it, the single byte `0x00`, then
+/// * the contents of the file.
+///
+/// The size is encoded before the contents so the boundary is unambiguous, and the flag
+/// byte keeps fingerprints with and without the modification time distinct.
+///
+/// # Errors
+/// An error will be returned if the file or its metadata cannot be read, if the
+/// modification time is before the UNIX epoch, or if the file changes size while it is
+/// read.
+///
+pub fn fingerprint_file<P: AsRef<Path>>(
+    path: P,
+    include_mtime: bool,
+) -> io::Result<[u8; SHA3_256_BYTES]> {
+    let mut file = File::open(path)?;
+    let metadata = file.metadata()?;
+    let size = usize::try_from(metadata.len()).map_err(io::Error::other)?;
+    let mut hasher = Sha3_256::new();
+    hasher
+        .update(&left_encode(size))
+        .map_err(io::Error::other)?;
+    if include_mtime {
+        let mtime = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map_err(io::Error::other)?;
+        let mut encoded = vec![0x01];
+        encoded.extend_from_slice(&mtime.as_secs().to_le_bytes());
+        encoded.extend_from_slice(&mtime.subsec_nanos().to_le_bytes());
+        hasher.update(&encoded).map_err(io::Error::other)?;
+    } else {
+        hasher.update(&[0x00]).map_err(io::Error::other)?;
+    }
+
+    let mut buf = [0u8; READ_BUFFER_BYTES];
+    let mut read = 0;
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(count) => {
+                hasher.update(&buf[..count]).map_err(io::Error::other)?;
+                read += count;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    if read != size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "file changed size while it was fingerprinted",
+        ));
+    }
+
+    let mut fingerprint = [0u8; SHA3_256_BYTES];
+    hasher
+        .finalize(&mut fingerprint)
+        .map_err(io::Error::other)?;
+    Ok(fingerprint)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs,
+        io::{self, Cursor, Read},
+    };
+
+    use anyhow::Result;
+    use bitvec::{order::Lsb0, slice::BitSlice};
+
+    use super::{fingerprint_file, hash_lines};
+    use crate::{
+        Hasher, HasherBits, SHA3_256_BYTES, Sha3_256, Shake128, XofHasher, nist_format,
+        sp800_185::encoding::left_encode,
+    };
+
+    // Returns at most `burst` bytes per read, and `WouldBlock` before every burst
+    struct BurstReader<'a> {
+        data: &'a [u8],
+        burst: usize,
+        blocked: bool,
+    }
+
+    impl Read for BurstReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.blocked = !self.blocked;
+            if self.blocked {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let count = self.burst.min(buf.len()).min(self.data.len());
+            buf[..count].copy_from_slice(&self.data[..count]);
+            self.data = &self.data[count..];
+            Ok(count)
+        }
+    }
+
+    fn digest(data: &[u8]) -> Result<[u8; SHA3_256_BYTES]> {
+        let mut hasher = Sha3_256::new();
+        let mut result = [0u8; SHA3_256_BYTES];
+        hasher.update(data)?;
+        hasher.finalize(&mut result)?;
+        Ok(result)
+    }
+
+    fn digest_lines(data: &[u8], normalize_crlf: bool) -> Result<[u8; SHA3_256_BYTES]> {
+        let mut hasher = Sha3_256::new();
+        let mut result = [0u8; SHA3_256_BYTES];
+        hash_lines(&mut hasher, Cursor::new(data), normalize_crlf)?;
+        hasher.finalize(&mut result)?;
+        Ok(result)
+    }
+
+    #[test]
+    fn test_hash_lines_normalize_crlf() -> Result<()> {
+        assert_eq!(digest(b"a\nb\n")?, digest_lines(b"a\r\nb\n", true)?);
+        assert_eq!(digest(b"a\nb\n")?, digest_lines(b"a\nb\n", true)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_lines_no_normalize_crlf() -> Result<()> {
+        assert_eq!(digest(b"a\r\nb\n")?, digest_lines(b"a\r\nb\n", false)?);
+        assert_ne!(digest(b"a\nb\n")?, digest_lines(b"a\r\nb\n", false)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_lines_missing_final_newline() -> Result<()> {
+        assert_eq!(digest(b"a\nb\n")?, digest_lines(b"a\nb", true)?);
+        assert_eq!(digest(b"")?, digest_lines(b"", true)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_lines_after_finalize_error() -> Result<()> {
+        let mut hasher = Sha3_256::new();
+        hasher.finalize(&mut [0u8; SHA3_256_BYTES])?;
+        assert!(hash_lines(&mut hasher, Cursor::new(b"a\n"), true).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_read_some_bursts() -> Result<()> {
+        let data = (0..=250).cycle().take(500).collect::<Vec<u8>>();
+        let mut reader = BurstReader {
+            data: &data,
+            burst: 7,
+            blocked: false,
+        };
+        let mut hasher = Sha3_256::new();
+        let mut total = 0;
+        loop {
+            match hasher.update_read_some(&mut reader, 64) {
+                Ok(0) => break,
+                Ok(count) => total += count,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let mut result = [0u8; SHA3_256_BYTES];
+        hasher.finalize(&mut result)?;
+        assert_eq!(data.len(), total);
+        assert_eq!(digest(&data)?, result);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_read_some_max_bytes() -> Result<()> {
+        let mut reader = Cursor::new(b"Hello, world!");
+        let mut hasher = Shake128::new();
+        assert_eq!(5, hasher.update_read_some(&mut reader, 5)?);
+        assert_eq!(8, hasher.update_read_some(&mut reader, 64)?);
+        assert_eq!(0, hasher.update_read_some(&mut reader, 64)?);
+
+        let mut expected = Shake128::new();
+        expected.update(b"Hello, world!")?;
+        assert_eq!(
+            expected.take(16).collect::<Vec<u8>>(),
+            hasher.take(16).collect::<Vec<u8>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_read_some_unlimited() -> Result<()> {
+        let data = (0..=250).cycle().take(20_000).collect::<Vec<u8>>();
+        let mut reader = Cursor::new(&data);
+        let mut hasher = Sha3_256::new();
+        let mut total = 0;
+        loop {
+            let count = hasher.update_read_some(&mut reader, usize::MAX)?;
+            if count == 0 {
+                break;
+            }
+            assert!(count <= 8192);
+            total += count;
+        }
+        let mut result = [0u8; SHA3_256_BYTES];
+        hasher.finalize(&mut result)?;
+        assert_eq!(data.len(), total);
+        assert_eq!(digest(&data)?, result);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_reader_bits_30_bits() -> Result<()> {
+        // The 30-bit SHA3-256 message, with the 2 unused high bits of the last byte set so
+        // they must be discarded, followed by bytes that must not be read.
+        let mut reader = Cursor::new([0x53u8, 0x58, 0x7b, 0xd9, 0xff]);
+        let mut hasher = Sha3_256::new();
+        hasher.update_reader_bits(&mut reader, 30)?;
+        assert_eq!(4, reader.position());
+        let mut result = [0u8; SHA3_256_BYTES];
+        hasher.finalize(&mut result)?;
+        assert_eq!(
+            "C8 24 2F EF 40 9E 5A E9 D1 F1 C8 57 AE 4D C6 24 B9 2B 19 80 9F 62 AA 8C 07 41 1C 54 A0 78 B1 D0",
+            nist_format(&result)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_reader_bits_short_read() {
+        let mut reader = Cursor::new([0x53u8, 0x58]);
+        let mut hasher = Sha3_256::new();
+        let err = hasher.update_reader_bits(&mut reader, 30).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+
+        // A hostile length prefix is read a block at a time, so it cannot force a huge
+        // allocation before the reader runs dry
+        let mut reader = Cursor::new([0xa5u8; 1000]);
+        let err = hasher
+            .update_reader_bits(&mut reader, usize::MAX)
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn test_update_reader_bits_spans_blocks() -> Result<()> {
+        // Several whole blocks followed by a trailing partial byte
+        let data = (0..=255).cycle().take(600).collect::<Vec<u8>>();
+        let total_bits = 599 * 8 + 5;
+        let mut hasher = Sha3_256::new();
+        hasher.update_reader_bits(&mut Cursor::new(&data), total_bits)?;
+        let mut result = [0u8; SHA3_256_BYTES];
+        hasher.finalize(&mut result)?;
+
+        let mut expected_hasher = Sha3_256::new();
+        expected_hasher.update_bits(&BitSlice::<u8, Lsb0>::from_slice(&data)[..total_bits])?;
+        let mut expected = [0u8; SHA3_256_BYTES];
+        expected_hasher.finalize(&mut expected)?;
+        assert_eq!(expected, result);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fingerprint_file_layout() -> Result<()> {
+        let contents = (0..=255).cycle().take(20_000).collect::<Vec<u8>>();
+        let path =
+            std::env::temp_dir().join(format!("shashasha-fingerprint-{}", std::process::id()));
+        fs::write(&path, &contents)?;
+        let fingerprint = fingerprint_file(&path, false);
+        let with_mtime = fingerprint_file(&path, true);
+        fs::remove_file(&path)?;
+
+        let mut expected = left_encode(contents.len());
+        expected.push(0x00);
+        expected.extend_from_slice(&contents);
+        assert_eq!(digest(&expected)?, fingerprint?);
+        assert_ne!(digest(&expected)?, with_mtime?);
+
+        assert!(fingerprint_file(&path, false).is_err());
+        Ok(())
+    }
+}
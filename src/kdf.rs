@@ -0,0 +1,247 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Key derivation helpers built on SHAKE and cSHAKE
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::{
+    CShake256, XofHasher,
+    sp800_185::encoding::{bytepad, encode_string},
+};
+
+/// Derive one key of `key_len` bytes per label from `secret`.
+///
+/// Each key is `cSHAKE256(secret, key_len * 8, "", label)`, so the labels are used as the
+/// cSHAKE customization string and properly domain separate the keys, rather than being
+/// concatenated with the secret.  The keys are returned in the same order as `labels`.
+///
+/// # Errors
+/// An error will be returned if any of the underlying cSHAKE256 operations fail.
+///
+pub fn derive_labeled_keys(
+    secret: &[u8],
+    labels: &[&[u8]],
+    key_len: usize,
+) -> Result<Vec<Vec<u8>>> {
+    labels
+        .iter()
+        .map(|label| derive_key(secret, label, key_len))
+        .collect()
+}
+
+/// Derive a set of named keys from `secret`, one per `(name, key_len)` field.
+///
+/// Each key is `cSHAKE256(secret, key_len * 8, "", name)`, exactly as
+/// [`derive_labeled_keys`] does with the UTF-8 bytes of `name` as the label, so every name
+/// gives an independent key.  If a name appears more than once, the last field with that
+/// name wins.
+///
+/// # Errors
+/// An error will be returned if any of the underlying cSHAKE256 operations fail.
+///
+pub fn derive_named(secret: &[u8], fields: &[(&str, usize)]) -> Result<HashMap<String, Vec<u8>>> {
+    fields
+        .iter()
+        .map(|(name, key_len)| {
+            Ok((
+                (*name).to_string(),
+                derive_key(secret, name.as_bytes(), *key_len)?,
+            ))
+        })
+        .collect()
+}
+
+/// Absorb `secret` into a cSHAKE256 instance with the function name `func_name` once, for
+/// deriving many outputs from it.
+///
+/// Each [`PreparedCShake::derive`] output is `cSHAKE256(encode_string(secret) || label,
+/// out_len * 8, func_name, "")`.  The secret is length encoded, so the boundary between
+/// the secret and the label is unambiguous.  Only the label is absorbed per derivation,
+/// which saves re-absorbing a long secret for every request.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::cshake_with_secret;
+/// # pub fn main() -> Result<()> {
+/// let prepared = cshake_with_secret(b"long-lived secret", b"session keys")?;
+/// let first = prepared.derive(b"request 1", 32)?;
+/// assert_ne!(first, prepared.derive(b"request 2", 32)?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if the underlying cSHAKE256 operations fail.
+///
+pub fn cshake_with_secret(secret: &[u8], func_name: &[u8]) -> Result<PreparedCShake> {
+    let mut xof = CShake256::new(func_name, b"")?;
+    xof.update(&encode_string(secret))?;
+    Ok(PreparedCShake { xof })
+}
+
+/// A cSHAKE256 instance with a secret already absorbed, created by [`cshake_with_secret`].
+#[derive(Clone, Debug)]
+pub struct PreparedCShake {
+    xof: CShake256,
+}
+
+impl PreparedCShake {
+    /// Derive `out_len` bytes for `label` from a copy of the prepared state.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying cSHAKE256 operations fail.
+    ///
+    pub fn derive(&self, label: &[u8], out_len: usize) -> Result<Vec<u8>> {
+        let mut xof = self.xof.clone();
+        let mut output = vec![0u8; out_len];
+        xof.update(label)?;
+        xof.finalize()?;
+        xof.get_bytes(&mut output, out_len)?;
+        Ok(output)
+    }
+}
+
+/// A keyed pseudorandom function, domain separated by `personalization`.
+///
+/// The output is `cSHAKE256(bytepad(encode_string(key), 136) || input, out_len * 8,
+/// personalization, "")`: the personalization is the cSHAKE function name, and the key is
+/// absorbed in its own padded block as in KMAC256, so every personalization gives an
+/// independent PRF even for the same key and input.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::prf;
+/// # pub fn main() -> Result<()> {
+/// let mac_key = prf(b"mac keys", b"master key", b"session 1", 32)?;
+/// let enc_key = prf(b"encryption keys", b"master key", b"session 1", 32)?;
+/// assert_ne!(mac_key, enc_key);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if the underlying cSHAKE256 operations fail.
+///
+pub fn prf(personalization: &[u8], key: &[u8], input: &[u8], out_len: usize) -> Result<Vec<u8>> {
+    let mut xof = CShake256::new(personalization, b"")?;
+    let mut output = vec![0u8; out_len];
+    xof.update(&bytepad(&encode_string(key), xof.optimal_update_size()))?;
+    xof.update(input)?;
+    xof.finalize()?;
+    xof.get_bytes(&mut output, out_len)?;
+    Ok(output)
+}
+
+fn derive_key(secret: &[u8], label: &[u8], key_len: usize) -> Result<Vec<u8>> {
+    let mut hasher = CShake256::new(b"", label)?;
+    let mut key = vec![0u8; key_len];
+    hasher.update(secret)?;
+    hasher.finalize()?;
+    hasher.get_bytes(&mut key, key_len)?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::{cshake_with_secret, derive_labeled_keys, derive_named, prf};
+    use crate::{
+        CShake256, XofHasher,
+        sp800_185::encoding::{bytepad, encode_string},
+    };
+
+    #[test]
+    fn test_derive_labeled_keys() -> Result<()> {
+        let keys = derive_labeled_keys(b"secret", &[b"enc", b"mac"], 16)?;
+        assert_eq!(2, keys.len());
+        assert_eq!(16, keys[0].len());
+        assert_eq!(16, keys[1].len());
+        assert_ne!(keys[0], keys[1]);
+
+        let mut hasher = CShake256::new(b"", b"enc")?;
+        hasher.update(b"secret")?;
+        assert_eq!(keys[0], hasher.take(16).collect::<Vec<u8>>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_derive_labeled_keys_swapped_labels_swap_keys() -> Result<()> {
+        let keys = derive_labeled_keys(b"secret", &[b"enc", b"mac"], 32)?;
+        let swapped = derive_labeled_keys(b"secret", &[b"mac", b"enc"], 32)?;
+        assert_eq!(keys[0], swapped[1]);
+        assert_eq!(keys[1], swapped[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_derive_labeled_keys_no_labels() -> Result<()> {
+        assert!(derive_labeled_keys(b"secret", &[], 32)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_derive_named() -> Result<()> {
+        let keys = derive_named(b"secret", &[("enc", 32), ("mac", 16)])?;
+        assert_eq!(2, keys.len());
+        assert_eq!(32, keys["enc"].len());
+        assert_eq!(16, keys["mac"].len());
+        assert_ne!(keys["enc"][..16], keys["mac"][..]);
+        assert_eq!(keys, derive_named(b"secret", &[("enc", 32), ("mac", 16)])?);
+
+        let labeled = derive_labeled_keys(b"secret", &[b"enc"], 32)?;
+        assert_eq!(labeled[0], keys["enc"]);
+
+        let renamed = derive_named(b"secret", &[("encryption", 32)])?;
+        assert_ne!(keys["enc"], renamed["encryption"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prepared_cshake_matches_from_scratch() -> Result<()> {
+        let secret = [0x5a; 200];
+        let prepared = cshake_with_secret(&secret, b"derive")?;
+        for label in [&b""[..], b"enc", b"mac", b"a longer label"] {
+            let mut hasher = CShake256::new(b"derive", b"")?;
+            hasher.update(&encode_string(&secret))?;
+            hasher.update(label)?;
+            assert_eq!(
+                hasher.take(48).collect::<Vec<u8>>(),
+                prepared.derive(label, 48)?
+            );
+        }
+        assert_ne!(prepared.derive(b"enc", 32)?, prepared.derive(b"mac", 32)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prf_personalizations_are_independent() -> Result<()> {
+        let a = prf(b"prf a", b"key", b"input", 64)?;
+        let b = prf(b"prf b", b"key", b"input", 64)?;
+        assert_eq!(64, a.len());
+        assert_ne!(a, b);
+        assert_eq!(a, prf(b"prf a", b"key", b"input", 64)?);
+        assert_eq!(a[..32], prf(b"prf a", b"key", b"input", 32)?);
+        assert_ne!(a, prf(b"prf a", b"other key", b"input", 64)?);
+        assert_ne!(a, prf(b"prf a", b"key", b"other input", 64)?);
+        // The key is length encoded, so moving bytes between key and input changes the output
+        assert_ne!(
+            prf(b"prf a", b"keyi", b"nput", 32)?,
+            prf(b"prf a", b"key", b"input", 32)?
+        );
+
+        let mut hasher = CShake256::new(b"prf a", b"")?;
+        hasher.update(&bytepad(&encode_string(b"key"), 136))?;
+        hasher.update(b"input")?;
+        assert_eq!(a, hasher.take(64).collect::<Vec<u8>>());
+        Ok(())
+    }
+}
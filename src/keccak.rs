@@ -97,59 +97,135 @@ pub fn f_1600(state: &mut [u64; LANE_COUNT]) -> Result<()> {
     p_1600(state, u64::KECCAK_F_ROUND_COUNT)
 }
 
-#[cfg_attr(feature = "unroll", unroll::unroll_for_loops)]
-#[cfg_attr(feature = "unroll", allow(unused_assignments))]
+/// Keccak-f permutation with width 1600 that records the state after every round.
+///
+/// Each entry pushed onto `trace` is the state after the θ, ρ, π, χ and ι steps of a
+/// single round have been applied, so a full permutation records 24 states.  This matches
+/// the "After Iota" lines of the XKCP intermediate values files.
+///
+/// # Errors
+///
+/// If the round count is larger than the round count for the given lane an error will be thrown.
+///
+#[cfg(feature = "trace")]
+pub fn f_1600_traced(
+    state: &mut [u64; LANE_COUNT],
+    trace: &mut Vec<[u64; LANE_COUNT]>,
+) -> Result<()> {
+    for round_const in round_consts::<u64>(u64::KECCAK_F_ROUND_COUNT)? {
+        round(state, *round_const)?;
+        trace.push(*state);
+    }
+    Ok(())
+}
+
 fn keccak_p<L: Lane>(state: &mut [L; LANE_COUNT], round_count: usize) -> Result<()> {
+    for round_const in round_consts::<L>(round_count)? {
+        round(state, *round_const)?;
+    }
+    Ok(())
+}
+
+fn round_consts<L: Lane>(round_count: usize) -> Result<&'static [u64]> {
     if round_count <= L::KECCAK_F_ROUND_COUNT {
-        let round_consts =
-            &ROUND_CONSTS[(L::KECCAK_F_ROUND_COUNT - round_count)..L::KECCAK_F_ROUND_COUNT];
-
-        for round_const in round_consts {
-            let mut array = [L::default(); 5];
-
-            for x in 0..5 {
-                for y in 0..5 {
-                    array[x] ^= state[5 * y + x];
-                }
-            }
-
-            // Theta
-            for x in 0..5 {
-                let parity_1 = array[(x + 4) % 5];
-                let parity_2 = array[(x + 1) % 5].rotate_left(1);
-                for y in 0..5 {
-                    state[5 * y + x] ^= parity_1 ^ parity_2;
-                }
-            }
-
-            // Pi and Rho
-            let mut last = state[1];
-            for x in 0..24 {
-                array[0] = state[PI[x]];
-                state[PI[x]] = last.rotate_left(RHO[x]);
-                last = array[0];
-            }
-
-            // Chi
-            for step in 0..5 {
-                let y = 5 * step;
-                array.copy_from_slice(&state[y..][..5]);
-
-                for x in 0..5 {
-                    let theta_1 = !array[(x + 1) % 5];
-                    let theta_2 = array[(x + 2) % 5];
-                    state[y + x] = array[x] ^ (theta_1 & theta_2);
-                }
-            }
-
-            // Iota
-            state[0] ^=
-                L::truncate(*round_const).map_err(|_| Sha3Error::TruncateFailed(*round_const))?;
+        Ok(&ROUND_CONSTS[(L::KECCAK_F_ROUND_COUNT - round_count)..L::KECCAK_F_ROUND_COUNT])
+    } else {
+        Err(Sha3Error::InvalidRoundCount(round_count).into())
+    }
+}
+
+#[cfg_attr(feature = "unroll", unroll::unroll_for_loops)]
+#[cfg_attr(feature = "unroll", allow(unused_assignments))]
+fn round<L: Lane>(state: &mut [L; LANE_COUNT], round_const: u64) -> Result<()> {
+    let mut array = [L::default(); 5];
+
+    for x in 0..5 {
+        for y in 0..5 {
+            array[x] ^= state[5 * y + x];
         }
+    }
 
-        Ok(())
+    // Theta
+    for x in 0..5 {
+        let parity_1 = array[(x + 4) % 5];
+        let parity_2 = array[(x + 1) % 5].rotate_left(1);
+        for y in 0..5 {
+            state[5 * y + x] ^= parity_1 ^ parity_2;
+        }
+    }
+
+    // Pi and Rho
+    let mut last = state[1];
+    for x in 0..24 {
+        array[0] = state[PI[x]];
+        state[PI[x]] = last.rotate_left(RHO[x]);
+        last = array[0];
+    }
+
+    // Chi
+    for step in 0..5 {
+        let y = 5 * step;
+        array.copy_from_slice(&state[y..][..5]);
+
+        for x in 0..5 {
+            let theta_1 = !array[(x + 1) % 5];
+            let theta_2 = array[(x + 2) % 5];
+            state[y + x] = array[x] ^ (theta_1 & theta_2);
+        }
+    }
+
+    // Iota
+    state[0] ^= L::truncate(round_const).map_err(|_| Sha3Error::TruncateFailed(round_const))?;
+
+    Ok(())
+}
+
+/// Read the bit `A[x][y][z]` of a Keccak-f\[1600\] state, using the coordinates of
+/// FIPS 202 §3.1.
+///
+/// The bit is bit `z` (counting from the least significant bit) of lane `5 * y + x`.
+///
+/// # Errors
+/// An error will be returned if `x` or `y` is not less than 5, or `z` is not less than 64.
+///
+#[cfg(feature = "low-level")]
+pub fn get_state_bit(state: &[u64; LANE_COUNT], x: usize, y: usize, z: usize) -> Result<bool> {
+    let (lane, bit) = state_bit_index(x, y, z)?;
+    Ok((state[lane] >> bit) & 1 == 1)
+}
+
+/// Set the bit `A[x][y][z]` of a Keccak-f\[1600\] state to `value`, using the coordinates
+/// of FIPS 202 §3.1.
+///
+/// The bit is bit `z` (counting from the least significant bit) of lane `5 * y + x`.
+///
+/// # Errors
+/// An error will be returned if `x` or `y` is not less than 5, or `z` is not less than 64.
+///
+#[cfg(feature = "low-level")]
+pub fn set_state_bit(
+    state: &mut [u64; LANE_COUNT],
+    x: usize,
+    y: usize,
+    z: usize,
+    value: bool,
+) -> Result<()> {
+    let (lane, bit) = state_bit_index(x, y, z)?;
+    if value {
+        state[lane] |= 1 << bit;
     } else {
-        Err(Sha3Error::InvalidRoundCount(round_count).into())
+        state[lane] &= !(1 << bit);
+    }
+    Ok(())
+}
+
+// The lane index and bit position of A[x][y][z]
+#[cfg(feature = "low-level")]
+fn state_bit_index(x: usize, y: usize, z: usize) -> Result<(usize, usize)> {
+    if x < 5 && y < 5 && z < 64 {
+        Ok((5 * y + x, z))
+    } else {
+        Err(Sha3Error::InvalidStateCoordinate(x, y, z).into())
     }
 }
 
@@ -346,4 +422,85 @@ mod test {
         assert_eq!(state, state_second);
         Ok(())
     }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn f_1600_traced_works() -> Result<()> {
+        use crate::f_1600_traced;
+
+        // Test vectors are copied from XKCP (eXtended Keccak Code Package)
+        // https://github.com/XKCP/XKCP/blob/master/tests/TestVectors/KeccakF-1600-IntermediateValues.txt
+        let mut round_one = [0u64; LANE_COUNT];
+        round_one[0] = 0x1;
+        let round_two = [
+            0x0_000_000_000_008_083,
+            0x0_000_100_000_000_000,
+            0x0_000_000_000_008_000,
+            0x0_000_000_000_000_001,
+            0x0_000_100_000_008_000,
+            0x0_000_000_000_000_000,
+            0x0_000_200_000_200_000,
+            0x0_000_000_000_000_000,
+            0x0_000_200_000_000_000,
+            0x0_000_000_000_200_000,
+            0x0_000_000_000_000_002,
+            0x0_000_000_000_000_200,
+            0x0_000_000_000_000_000,
+            0x0_000_000_000_000_202,
+            0x0_000_000_000_000_000,
+            0x0_000_000_010_000_400,
+            0x0_000_000_000_000_000,
+            0x0_000_000_000_000_400,
+            0x0_000_000_010_000_000,
+            0x0_000_000_000_000_000,
+            0x0_000_010_000_000_000,
+            0x0_000_000_000_000_000,
+            0x0_000_010_000_000_004,
+            0x0_000_000_000_000_000,
+            0x0_000_000_000_000_004,
+        ];
+
+        let mut state = [0u64; LANE_COUNT];
+        let mut expected = [0u64; LANE_COUNT];
+        let mut trace = Vec::new();
+        f_1600_traced(&mut state, &mut trace)?;
+        f_1600(&mut expected)?;
+        assert_eq!(24, trace.len());
+        assert_eq!(round_one, trace[0]);
+        assert_eq!(round_two, trace[1]);
+        assert_eq!(expected, trace[23]);
+        assert_eq!(0xF_125_8F7_940_E1D_DE7, trace[23][0]);
+        assert_eq!(expected, state);
+        Ok(())
+    }
+
+    #[cfg(feature = "low-level")]
+    #[test]
+    fn test_state_bit_coordinates() -> Result<()> {
+        use crate::{get_state_bit, set_state_bit};
+
+        let mut state = [0u64; LANE_COUNT];
+        set_state_bit(&mut state, 1, 2, 7, true)?;
+        // A[1][2][7] is bit 7 of lane 5 * 2 + 1
+        assert_eq!(1 << 7, state[11]);
+        assert!(get_state_bit(&state, 1, 2, 7)?);
+        assert!(!get_state_bit(&state, 2, 1, 7)?);
+        set_state_bit(&mut state, 1, 2, 7, false)?;
+        assert_eq!([0u64; LANE_COUNT], state);
+
+        // A single set bit A[0][0][0] through Keccak-f[1600]
+        set_state_bit(&mut state, 0, 0, 0, true)?;
+        f_1600(&mut state)?;
+        assert_eq!(0xe2a9_4439_6f0b_13c6, state[0]);
+        assert_eq!(0x70fe_c06c_eb0b_06c4, state[1]);
+        assert_eq!(0xf5a8_4375_5d53_74af, state[24]);
+        // Lane 0 ends in 0xc6, so bit 1 is set and bit 0 is not
+        assert!(get_state_bit(&state, 0, 0, 1)?);
+        assert!(!get_state_bit(&state, 0, 0, 0)?);
+
+        assert!(get_state_bit(&state, 5, 0, 0).is_err());
+        assert!(get_state_bit(&state, 0, 5, 0).is_err());
+        assert!(set_state_bit(&mut state, 0, 0, 64, true).is_err());
+        Ok(())
+    }
 }
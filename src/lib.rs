@@ -82,6 +82,41 @@
 //! # }
 //! ```
 //!
+//! ## Features
+//!
+//! The SHA-3 hash functions and the SHAKE XOFs are always available.  The SP 800-185
+//! derived functions (cSHAKE, and the key derivation built on it) are a larger surface, so
+//! they are only compiled with the `sp800-185` feature enabled:
+//!
+//! ```toml
+//! shashasha = { version = "0.3", features = ["sp800-185"] }
+//! ```
+//!
+#![cfg_attr(not(feature = "sp800-185"), doc = "```compile_fail")]
+#![cfg_attr(feature = "sp800-185", doc = "```")]
+//! # use anyhow::Result;
+//! # use shashasha::{CShake256, XofHasher};
+//! # pub fn main() -> Result<()> {
+//! let mut hasher = CShake256::new(b"", b"Email Signature")?;
+//! hasher.update(b"Hello, world!")?;
+//! hasher.finalize()?;
+//! #     Ok(())
+//! # }
+//! ```
+//!
+//! The remaining features are:
+//!
+//! * `low-level` - exposes the `Keccak1600Sponge` the hashers are built on, which can be
+//!   updated, finalized with any domain separation suffix and squeezed directly, and
+//!   `get_state_bit`/`set_state_bit` for addressing state bits by FIPS 202 coordinates.
+//! * `trace` - adds `f_1600_traced`, which records the state after every round.
+//! * `unroll` - unrolls the Keccak-f round loop.
+//! * `rayon` - hashes tree chunks in parallel.
+//! * `serde` - adds `hash_canonical`, which hashes a serializable value in a canonical CBOR
+//!   encoding.
+//! * `tokio` - adds `update_async`, which absorbs everything from a tokio `AsyncRead`, and
+//!   `Sha3Writer`, a tokio `AsyncWrite` sink that hashes everything written to it.
+//! * `zeroize` - adds `update_and_zeroize`, which wipes a sensitive input after absorbing it.
 
 // rustc lints
 #![cfg_attr(
@@ -307,41 +342,164 @@
 )]
 #![cfg_attr(all(docsrs, nightly), feature(doc_cfg))]
 
+mod absorb;
+mod aligned;
+#[cfg(feature = "tokio")]
+mod async_writer;
+mod bitstream;
+mod bloom;
+mod builder;
+#[cfg(feature = "serde")]
+mod canonical;
+mod checkpoint;
+mod commitment;
 mod constants;
 mod error;
+mod io;
+#[cfg(feature = "sp800-185")]
+mod kdf;
 mod keccak;
 mod lane;
+mod mac;
+mod midstate;
+mod oneshot;
+mod pow;
+mod prng;
+mod rolling;
 mod sha3;
 mod shake;
+mod sp800_185;
 mod sponge;
+mod std_hash;
+mod throughput;
 mod traits;
+mod transcript;
+mod tree;
 mod utils;
+mod words;
 
+pub use self::aligned::AlignedBytes;
+pub use self::aligned::SIMD_ALIGN_BYTES;
+#[cfg(feature = "tokio")]
+pub use self::async_writer::Sha3Writer;
+pub use self::bitstream::BitStreamHasher;
+pub use self::bloom::bloom_indices;
+pub use self::builder::KeccakBuilder;
+pub use self::builder::KeccakHasher;
+pub use self::builder::fast_hash_256;
+pub use self::builder::identify_params;
+#[cfg(feature = "serde")]
+pub use self::canonical::hash_canonical;
+pub use self::checkpoint::CheckpointHasher;
+pub use self::commitment::COMMITMENT_BYTES;
+pub use self::commitment::Shake256Commitment;
 pub use self::constants::LANE_COUNT;
+pub use self::constants::MIN_TAG_BYTES;
 pub use self::constants::SHA3_224_BYTES;
 pub use self::constants::SHA3_256_BYTES;
 pub use self::constants::SHA3_384_BYTES;
 pub use self::constants::SHA3_512_BYTES;
 pub use self::error::Sha3Error;
+pub use self::io::fingerprint_file;
+pub use self::io::hash_lines;
+#[cfg(feature = "sp800-185")]
+pub use self::kdf::PreparedCShake;
+#[cfg(feature = "sp800-185")]
+pub use self::kdf::cshake_with_secret;
+#[cfg(feature = "sp800-185")]
+pub use self::kdf::derive_labeled_keys;
+#[cfg(feature = "sp800-185")]
+pub use self::kdf::derive_named;
+#[cfg(feature = "sp800-185")]
+pub use self::kdf::prf;
 pub use self::keccak::f_200;
 pub use self::keccak::f_400;
 pub use self::keccak::f_800;
 pub use self::keccak::f_1600;
+#[cfg(feature = "trace")]
+pub use self::keccak::f_1600_traced;
+#[cfg(feature = "low-level")]
+pub use self::keccak::get_state_bit;
 pub use self::keccak::p_200;
 pub use self::keccak::p_400;
 pub use self::keccak::p_800;
 pub use self::keccak::p_1600;
+#[cfg(feature = "low-level")]
+pub use self::keccak::set_state_bit;
+pub use self::mac::shake_mac;
+pub use self::mac::sphincs_prf;
+pub use self::midstate::Midstate;
+pub use self::midstate::SharedPrefixHasher;
+pub use self::oneshot::auto_hash_256;
+pub use self::oneshot::double_sha3_256;
+pub use self::oneshot::hash_n_bytes;
+pub use self::oneshot::hash_then_expand;
+pub use self::oneshot::rehash_256;
+pub use self::oneshot::sha3_224;
+pub use self::oneshot::sha3_224_bits;
+pub use self::oneshot::sha3_256;
+pub use self::oneshot::sha3_256_bits;
+pub use self::oneshot::sha3_384;
+pub use self::oneshot::sha3_384_bits;
+pub use self::oneshot::sha3_512;
+pub use self::oneshot::sha3_512_bits;
+pub use self::oneshot::shake128;
+pub use self::oneshot::shake128_bits;
+pub use self::oneshot::shake256;
+pub use self::oneshot::shake256_bits;
+pub use self::pow::check_pow;
+pub use self::prng::KeccakPrng;
+pub use self::prng::derive_nonce;
+pub use self::prng::derive_salt;
+pub use self::rolling::RollingDigest;
+pub use self::sha3::scratch::ScratchHasher;
+pub use self::sha3::scratch::Sha3Scratch;
 pub use self::sha3::sha224::Sha3_224;
 pub use self::sha3::sha256::Sha3_256;
 pub use self::sha3::sha384::Sha3_384;
 pub use self::sha3::sha512::Sha3_512;
+pub use self::shake::first_divergence;
+pub use self::shake::interleave_xof;
+pub use self::shake::shake_for_security;
 pub use self::shake::shake128::Shake128;
+pub use self::shake::shake128::Shake128Xof;
 pub use self::shake::shake256::Shake256;
+pub use self::shake::shake256::Shake256Xof;
+#[cfg(feature = "sp800-185")]
+pub use self::sp800_185::cshake::CShake128;
+#[cfg(feature = "sp800-185")]
+pub use self::sp800_185::cshake::CShake256;
+#[cfg(feature = "low-level")]
+pub use self::sponge::Endian;
+#[cfg(feature = "low-level")]
+pub use self::sponge::Keccak1600Sponge;
+#[cfg(feature = "low-level")]
+pub use self::sponge::compute_padding;
+pub use self::std_hash::Sha3BuildHasher;
+pub use self::std_hash::Sha3Hasher;
+pub use self::throughput::measure_throughput;
+pub use self::traits::DynHasher;
+pub use self::traits::DynXof;
 pub use self::traits::Hasher;
 pub use self::traits::HasherBits;
 pub use self::traits::XofHasher;
 pub use self::traits::XofHasherBits;
+pub use self::transcript::Transcript;
+pub use self::tree::TREE_CHUNK_SIZE;
+pub use self::tree::sha3_256_tree;
+pub use self::tree::sha3_256_tree_with_proof;
+pub use self::tree::verify_merkle_proof;
+pub use self::utils::b2bits_string;
 pub use self::utils::b2h;
+pub use self::utils::b2h_fmt;
+pub use self::utils::b2h_into;
+pub use self::utils::b2h_strict;
+pub use self::utils::bits_from_hex;
+pub use self::utils::bits_from_string;
+pub use self::utils::digest_diff;
+pub use self::utils::fold_digest;
+pub use self::utils::nist_format;
+pub use self::words::fingerprint_words;
 pub use bitvec::prelude::BitSlice;
 pub use bitvec::prelude::BitVec;
 pub use bitvec::prelude::Lsb0;
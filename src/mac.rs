@@ -0,0 +1,134 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Message authentication codes and keyed hashes built on SHAKE
+
+use anyhow::Result;
+
+use crate::{
+    Shake256, XofHasher,
+    constants::SHAKE_256_RATE,
+    sp800_185::encoding::{bytepad, encode_string},
+};
+
+/// A simple prefix MAC producing an `out_len` byte tag for `msg` under `key`.
+///
+/// The tag is `SHAKE256(bytepad(encode_string(key), 136) || msg, out_len * 8)`.  The key
+/// is length encoded before padding, so keys that differ only in trailing zero bytes give
+/// different tags, and padding it to the 136 byte rate means the key fills whole blocks
+/// before any message data is absorbed.
+///
+/// This is **not** KMAC (SP 800-185): there is no function name or customization string,
+/// and the requested output length is not bound into the tag, so a shorter tag is a
+/// prefix of a longer one for the same key and message.  Use it only where every party
+/// agrees on a fixed `out_len`, the key is secret and uniformly random (at least 32
+/// bytes), and tags are compared in constant time.
+///
+/// # Errors
+/// An error will be returned if the underlying SHAKE256 operations fail.
+///
+pub fn shake_mac(key: &[u8], msg: &[u8], out_len: usize) -> Result<Vec<u8>> {
+    let mut hasher = Shake256::new();
+    let mut tag = vec![0u8; out_len];
+    hasher.update(&bytepad(&encode_string(key), SHAKE_256_RATE / 8))?;
+    hasher.update(msg)?;
+    hasher.finalize()?;
+    hasher.get_bytes(&mut tag, out_len)?;
+    Ok(tag)
+}
+
+/// The SHAKE256 keyed hash used by the SPHINCS+ (SLH-DSA) SHAKE parameter sets.
+///
+/// The output is `SHAKE256(key || message, out_len * 8)`: the key bytes are absorbed
+/// first, directly followed by the message bytes, with no length encoding or padding in
+/// between.  For the SPHINCS+ `PRF(PK.seed, SK.seed, ADRS)` pass `PK.seed` as the key and
+/// `ADRS || SK.seed` as the message; `PRF_msg` and `H_msg` follow the same pattern with
+/// their inputs concatenated in specification order.  Because nothing separates the key
+/// from the message, `("ab", "c")` and `("a", "bc")` give the same output, so the key
+/// length must be fixed by the scheme.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::sphincs_prf;
+/// # pub fn main() -> Result<()> {
+/// let pk_seed = [0u8; 16];
+/// let adrs_sk_seed = [1u8; 48];
+/// assert_eq!(16, sphincs_prf(&pk_seed, &adrs_sk_seed, 16)?.len());
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if the underlying SHAKE256 operations fail.
+///
+pub fn sphincs_prf(key: &[u8], message: &[u8], out_len: usize) -> Result<Vec<u8>> {
+    let mut xof = Shake256::new();
+    let mut output = vec![0u8; out_len];
+    xof.update(key)?;
+    xof.update(message)?;
+    xof.finalize()?;
+    xof.get_bytes(&mut output, out_len)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use bitvec::{order::Lsb0, vec::BitVec};
+
+    use super::{shake_mac, sphincs_prf};
+    use crate::{b2h, nist_format};
+
+    #[test]
+    fn test_shake_mac() -> Result<()> {
+        let tag = shake_mac(b"key", b"message", 32)?;
+        assert_eq!(
+            "d0073d38823b3461d29913c54669ba95ad22ebfcc413f8391a25ce46edbe38c8",
+            b2h(&BitVec::<u8, Lsb0>::from_slice(&tag), false, false)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shake_mac_key_change_alters_tag() -> Result<()> {
+        let tag = shake_mac(b"key", b"message", 32)?;
+        let other = shake_mac(b"key2", b"message", 32)?;
+        assert_eq!(
+            "b745865be208a5f0791870d31ad568d0ed292549c83d3e4b54e3e824bf343ca4",
+            b2h(&BitVec::<u8, Lsb0>::from_slice(&other), false, false)?
+        );
+        assert_ne!(tag, other);
+        assert_ne!(tag, shake_mac(b"key\0", b"message", 32)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sphincs_prf() -> Result<()> {
+        // PRF(PK.seed, SK.seed, ADRS) with PK.seed = 00..0F, ADRS = 0^32, SK.seed = 10..1F
+        let pk_seed = (0..16).collect::<Vec<u8>>();
+        let mut message = vec![0u8; 32];
+        message.extend(16..32);
+        assert_eq!(
+            "FB 55 18 A3 DB 0A 23 39 92 72 11 58 B8 97 D5 2F",
+            nist_format(&sphincs_prf(&pk_seed, &message, 16)?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sphincs_prf_is_plain_concatenation() -> Result<()> {
+        let prf = sphincs_prf(b"key", b"message", 32)?;
+        assert_eq!(
+            "8F D9 53 D4 17 B0 1E 62 BA 2F FE 55 7D DE 99 D6 73 5F 6F B4 A0 09 5C 5F 25 FB 7B 6B 3C 37 48 53",
+            nist_format(&prf)
+        );
+        assert_eq!(prf, sphincs_prf(b"keymess", b"age", 32)?);
+        assert_eq!(prf[..16], sphincs_prf(b"key", b"message", 16)?);
+        Ok(())
+    }
+}
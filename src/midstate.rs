@@ -0,0 +1,169 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Resumable hasher midstates
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use bitvec::{order::Lsb0, slice::BitSlice, vec::BitVec};
+
+use crate::{Hasher, SHA3_256_BYTES, Sha3_256, constants::LANE_COUNT};
+
+/// A compact resume token capturing a SHA3 hasher after it has absorbed a prefix.
+///
+/// A midstate is the Keccak-f\[1600\] state after the last complete rate block, plus the
+/// trailing bits of the prefix that do not yet fill a block.  Exporting one after a fixed
+/// prefix with `export_midstate` lets many different suffixes be hashed with
+/// `from_midstate` without absorbing the prefix again.
+///
+/// A midstate is tied to the hasher it was exported from; resuming it as a hasher with a
+/// different rate is an error.  It is not a serialization format and the state it holds
+/// reveals as much about the prefix as the hasher itself does.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Midstate {
+    pub(crate) state: [u64; LANE_COUNT],
+    pub(crate) remainder: BitVec<u8, Lsb0>,
+    pub(crate) rate: usize,
+    pub(crate) bits_absorbed: usize,
+    pub(crate) suffix: &'static BitSlice<u8, Lsb0>,
+}
+
+impl Midstate {
+    /// The Keccak-f\[1600\] state after the last complete rate block of the prefix.
+    #[must_use]
+    pub fn state(&self) -> &[u64; LANE_COUNT] {
+        &self.state
+    }
+
+    /// The trailing prefix bits that have not yet been absorbed into the state.
+    #[must_use]
+    pub fn remainder(&self) -> &BitSlice<u8, Lsb0> {
+        &self.remainder
+    }
+
+    /// The rate of the sponge, in bits, that this midstate was exported from.
+    #[must_use]
+    pub fn rate(&self) -> usize {
+        self.rate
+    }
+}
+
+/// A SHA3-256 hasher for `prefix || suffix` with a fixed prefix, shared between threads.
+///
+/// The prefix is absorbed once and its [`Midstate`] is kept behind an [`Arc`], so cloning
+/// a `SharedPrefixHasher` is cheap and every clone (or a `&SharedPrefixHasher`) can hash
+/// suffixes concurrently.  Each call to [`hash_with_suffix`](Self::hash_with_suffix)
+/// resumes its own hasher from the midstate and never modifies the shared state.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{SharedPrefixHasher, sha3_256};
+/// # pub fn main() -> Result<()> {
+/// let hasher = SharedPrefixHasher::new(b"shared header ")?;
+/// let expected = sha3_256(b"shared header request")?;
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         let _ = scope.spawn(|| {
+///             assert_eq!(expected, hasher.hash_with_suffix(b"request").unwrap_or_default());
+///         });
+///     }
+/// });
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SharedPrefixHasher {
+    midstate: Arc<Midstate>,
+}
+
+impl SharedPrefixHasher {
+    /// Absorb `prefix` into a new SHA3-256 hasher and share its midstate.
+    ///
+    /// # Errors
+    /// An error will be returned if the prefix cannot be absorbed.
+    ///
+    pub fn new(prefix: &[u8]) -> Result<Self> {
+        let mut hasher = Sha3_256::new();
+        hasher.update(prefix)?;
+        Ok(Self {
+            midstate: Arc::new(hasher.export_midstate()?),
+        })
+    }
+
+    /// Compute the SHA3-256 digest of `prefix || suffix`.
+    ///
+    /// # Errors
+    /// An error will be returned if the suffix cannot be absorbed or the hash fails.
+    ///
+    pub fn hash_with_suffix(&self, suffix: &[u8]) -> Result<[u8; SHA3_256_BYTES]> {
+        let mut hasher = Sha3_256::from_midstate(&self.midstate)?;
+        let mut digest = [0u8; SHA3_256_BYTES];
+        hasher.update(suffix)?;
+        hasher.finalize(&mut digest)?;
+        Ok(digest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use anyhow::Result;
+
+    use super::{Midstate, SharedPrefixHasher};
+    use crate::{
+        KeccakHasher, Sha3_224, Sha3_256, Sha3_384, Sha3_512, Shake128, Shake256, sha3_256,
+    };
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_hashers_are_send_sync() {
+        assert_send_sync::<Sha3_224>();
+        assert_send_sync::<Sha3_256>();
+        assert_send_sync::<Sha3_384>();
+        assert_send_sync::<Sha3_512>();
+        assert_send_sync::<Shake128>();
+        assert_send_sync::<Shake256>();
+        assert_send_sync::<KeccakHasher>();
+        assert_send_sync::<Midstate>();
+        assert_send_sync::<SharedPrefixHasher>();
+    }
+
+    #[test]
+    fn test_shared_prefix_across_threads() -> Result<()> {
+        // Longer than the rate, so the midstate holds a permuted state and a remainder
+        let prefix = (0..=255).cycle().take(300).collect::<Vec<u8>>();
+        let hasher = SharedPrefixHasher::new(&prefix)?;
+        let suffixes = (0..8u8)
+            .map(|i| vec![i; usize::from(i) * 50])
+            .collect::<Vec<Vec<u8>>>();
+
+        let digests = thread::scope(|scope| {
+            let handles = suffixes
+                .iter()
+                .map(|suffix| {
+                    let hasher = hasher.clone();
+                    scope.spawn(move || hasher.hash_with_suffix(suffix))
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|_| Ok([0u8; 32])))
+                .collect::<Result<Vec<_>>>()
+        })?;
+
+        for (suffix, digest) in suffixes.iter().zip(digests) {
+            let mut message = prefix.clone();
+            message.extend_from_slice(suffix);
+            assert_eq!(sha3_256(&message)?, digest);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,455 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! One-shot hashing helpers
+
+use anyhow::Result;
+use bitvec::{order::Lsb0, slice::BitSlice};
+
+use crate::{
+    Hasher, HasherBits, LANE_COUNT, SHA3_224_BYTES, SHA3_256_BYTES, SHA3_384_BYTES, SHA3_512_BYTES,
+    Sha3_224, Sha3_256, Sha3_384, Sha3_512, Shake128, Shake256, XofHasher, XofHasherBits,
+    constants::SHA3_256_RATE, f_1600,
+};
+
+// Inputs longer than this (in bytes) are streamed by `auto_hash_256`
+const AUTO_STREAM_THRESHOLD: usize = 64 * 1024;
+// 64 rate blocks of SHA3-256, so every chunk ends on a block boundary
+const AUTO_STREAM_CHUNK_BYTES: usize = 64 * SHA3_256_RATE / 8;
+
+/// Compute the SHA3-224 digest of `data` in one call.
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-224 operations fail.
+///
+pub fn sha3_224(data: &[u8]) -> Result<[u8; SHA3_224_BYTES]> {
+    digest(Sha3_224::new(), data)
+}
+
+/// Compute the SHA3-224 digest of the bit string `bits` in one call.
+///
+/// The length of `bits` need not be a multiple of 8, as in the NIST bit-oriented test
+/// vectors.
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-224 operations fail.
+///
+pub fn sha3_224_bits(bits: &BitSlice<u8, Lsb0>) -> Result<[u8; SHA3_224_BYTES]> {
+    digest_bits(Sha3_224::new(), bits)
+}
+
+/// Compute the SHA3-256 digest of `data` in one call.
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-256 operations fail.
+///
+pub fn sha3_256(data: &[u8]) -> Result<[u8; SHA3_256_BYTES]> {
+    digest(Sha3_256::new(), data)
+}
+
+/// Compute the SHA3-256 digest of the bit string `bits` in one call.
+///
+/// The length of `bits` need not be a multiple of 8, as in the NIST bit-oriented test
+/// vectors.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{BitVec, Lsb0, b2h, bits, sha3_256_bits};
+/// # pub fn main() -> Result<()> {
+/// // The 5-bit SHA3-256 example from the NIST test vectors
+/// let digest = sha3_256_bits(bits![u8, Lsb0; 1, 1, 0, 0, 1])?;
+/// let res = b2h(&BitVec::<u8, Lsb0>::from_slice(&digest), false, false)?;
+/// assert_eq!("7b0047cf5a456882363cbf0fb05322cf65f4b7059a46365e830132e3b5d957af", res);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-256 operations fail.
+///
+pub fn sha3_256_bits(bits: &BitSlice<u8, Lsb0>) -> Result<[u8; SHA3_256_BYTES]> {
+    digest_bits(Sha3_256::new(), bits)
+}
+
+/// Compute the SHA3-384 digest of `data` in one call.
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-384 operations fail.
+///
+pub fn sha3_384(data: &[u8]) -> Result<[u8; SHA3_384_BYTES]> {
+    digest(Sha3_384::new(), data)
+}
+
+/// Compute the SHA3-384 digest of the bit string `bits` in one call.
+///
+/// The length of `bits` need not be a multiple of 8, as in the NIST bit-oriented test
+/// vectors.
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-384 operations fail.
+///
+pub fn sha3_384_bits(bits: &BitSlice<u8, Lsb0>) -> Result<[u8; SHA3_384_BYTES]> {
+    digest_bits(Sha3_384::new(), bits)
+}
+
+/// Compute the SHA3-512 digest of `data` in one call.
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-512 operations fail.
+///
+pub fn sha3_512(data: &[u8]) -> Result<[u8; SHA3_512_BYTES]> {
+    digest(Sha3_512::new(), data)
+}
+
+/// Compute the SHA3-512 digest of the bit string `bits` in one call.
+///
+/// The length of `bits` need not be a multiple of 8, as in the NIST bit-oriented test
+/// vectors.
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-512 operations fail.
+///
+pub fn sha3_512_bits(bits: &BitSlice<u8, Lsb0>) -> Result<[u8; SHA3_512_BYTES]> {
+    digest_bits(Sha3_512::new(), bits)
+}
+
+/// Compute `out_len` bytes of SHAKE128 output for `data` in one call.
+///
+/// # Errors
+/// An error will be returned if the underlying SHAKE128 operations fail.
+///
+pub fn shake128(data: &[u8], out_len: usize) -> Result<Vec<u8>> {
+    xof(Shake128::new(), data, out_len)
+}
+
+/// Compute `out_len` bytes of SHAKE128 output for the bit string `bits` in one call.
+///
+/// # Errors
+/// An error will be returned if the underlying SHAKE128 operations fail.
+///
+pub fn shake128_bits(bits: &BitSlice<u8, Lsb0>, out_len: usize) -> Result<Vec<u8>> {
+    xof_bits(Shake128::new(), bits, out_len)
+}
+
+/// Compute `out_len` bytes of SHAKE256 output for `data` in one call.
+///
+/// # Errors
+/// An error will be returned if the underlying SHAKE256 operations fail.
+///
+pub fn shake256(data: &[u8], out_len: usize) -> Result<Vec<u8>> {
+    xof(Shake256::new(), data, out_len)
+}
+
+/// Compute `out_len` bytes of SHAKE256 output for the bit string `bits` in one call.
+///
+/// # Errors
+/// An error will be returned if the underlying SHAKE256 operations fail.
+///
+pub fn shake256_bits(bits: &BitSlice<u8, Lsb0>, out_len: usize) -> Result<Vec<u8>> {
+    xof_bits(Shake256::new(), bits, out_len)
+}
+
+/// Compute `n` bytes of hash output for `data`, selecting the primitive from `n`.
+///
+/// The primitive is chosen as follows, and the output of each is truncated to `n` bytes:
+///
+/// | `n`          | primitive |
+/// |--------------|-----------|
+/// | `0..=28`     | SHA3-224  |
+/// | `29..=32`    | SHA3-256  |
+/// | `33..=48`    | SHA3-384  |
+/// | `49..=64`    | SHA3-512  |
+/// | `65..`       | SHAKE256  |
+///
+/// Because the primitive depends on `n`, outputs for different lengths are unrelated (e.g.
+/// the 16 byte output is not a prefix of the 32 byte output), and these rules will not
+/// change within a major version.
+///
+/// # Errors
+/// An error will be returned if the underlying hash operations fail.
+///
+pub fn hash_n_bytes(data: &[u8], n: usize) -> Result<Vec<u8>> {
+    let mut output = match n {
+        0..=SHA3_224_BYTES => sha3_224(data)?.to_vec(),
+        29..=SHA3_256_BYTES => sha3_256(data)?.to_vec(),
+        33..=SHA3_384_BYTES => sha3_384(data)?.to_vec(),
+        49..=SHA3_512_BYTES => sha3_512(data)?.to_vec(),
+        _ => return shake256(data, n),
+    };
+    output.truncate(n);
+    Ok(output)
+}
+
+/// Hash `secret` with SHA3-256, then expand the 32 byte digest with SHAKE256 to `out_len`
+/// bytes.
+///
+/// The output is `SHAKE256(SHA3-256(secret), out_len * 8)`.  Only the digest is fed to
+/// SHAKE256, so outputs of different lengths for the same secret share a prefix.
+///
+/// # Errors
+/// An error will be returned if the underlying hash operations fail.
+///
+pub fn hash_then_expand(secret: &[u8], out_len: usize) -> Result<Vec<u8>> {
+    shake256(&sha3_256(secret)?, out_len)
+}
+
+/// Compute the SHA3-256 digest of a SHA3-256 digest, i.e. `SHA3-256(digest)`.
+///
+/// A 32 byte message, its domain separation bits, and its padding fit in a single
+/// 136 byte block, so this absorbs the block directly into the state and runs one
+/// Keccak-f\[1600\] permutation without allocating.  The result is identical to
+/// `sha3_256(digest)`.
+///
+/// # Panics
+/// Never in practice: Keccak-f\[1600\] can only fail for an invalid round count, and it
+/// always uses 24 rounds.
+///
+#[must_use]
+pub fn rehash_256(digest: &[u8; SHA3_256_BYTES]) -> [u8; SHA3_256_BYTES] {
+    // This is the byte-level equivalent of what the sponge does for SHA3-256: the suffix
+    // and `pad10star1` in `Keccak1600Sponge::finalize`, then the little-endian lane load
+    // in `xor_block` (both in `sponge/mod.rs`).  `test_rehash_256` pins it to `sha3_256`.
+    let mut block = [0u8; SHA3_256_RATE / 8];
+    block[..SHA3_256_BYTES].copy_from_slice(digest);
+    // The SHA-3 suffix `01` followed by the first `1` of `pad10*1`...
+    block[SHA3_256_BYTES] = 0b0000_0110;
+    // ...and the final `1` in the last bit of the block
+    block[SHA3_256_RATE / 8 - 1] |= 0b1000_0000;
+
+    let mut state = [0u64; LANE_COUNT];
+    for (lane, bytes) in state.iter_mut().zip(block.chunks_exact(8)) {
+        let mut lane_bytes = [0u8; 8];
+        lane_bytes.copy_from_slice(bytes);
+        *lane = u64::from_le_bytes(lane_bytes);
+    }
+
+    f_1600(&mut state).expect("Keccak-f[1600] always uses a valid round count");
+    let mut output = [0u8; SHA3_256_BYTES];
+    for (bytes, lane) in output.chunks_exact_mut(8).zip(state) {
+        bytes.copy_from_slice(&lane.to_le_bytes());
+    }
+    output
+}
+
+/// Compute the double SHA3-256 digest of `data`, i.e. `SHA3-256(SHA3-256(data))`.
+///
+/// The outer hash is computed with [`rehash_256`], so it needs no second hasher.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{double_sha3_256, sha3_256};
+/// # pub fn main() -> Result<()> {
+/// assert_eq!(sha3_256(&sha3_256(b"x")?)?, double_sha3_256(b"x")?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-256 operations fail.
+///
+pub fn double_sha3_256(data: &[u8]) -> Result<[u8; SHA3_256_BYTES]> {
+    Ok(rehash_256(&sha3_256(data)?))
+}
+
+/// Compute the standard SHA3-256 digest of `data`, choosing how to absorb it from its
+/// length.
+///
+/// Inputs of up to 64 KiB are absorbed in a single update, as with [`sha3_256`].  Larger
+/// inputs are streamed in chunks that are a whole number of 136 byte rate blocks, so the
+/// hasher never buffers more than one chunk of the input at a time.  Either way the result
+/// is the same SHA3-256 digest, so callers need not think about update granularity.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{auto_hash_256, sha3_256};
+/// # pub fn main() -> Result<()> {
+/// assert_eq!(sha3_256(b"abc")?, auto_hash_256(b"abc")?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-256 operations fail.
+///
+pub fn auto_hash_256(data: &[u8]) -> Result<[u8; SHA3_256_BYTES]> {
+    let mut hasher = Sha3_256::new();
+    if data.len() <= AUTO_STREAM_THRESHOLD {
+        digest(hasher, data)
+    } else {
+        let mut output = [0u8; SHA3_256_BYTES];
+        for chunk in data.chunks(AUTO_STREAM_CHUNK_BYTES) {
+            hasher.update(chunk)?;
+        }
+        hasher.finalize(&mut output)?;
+        Ok(output)
+    }
+}
+
+fn digest<const D_BYTES: usize, H: Hasher<D_BYTES>>(
+    mut hasher: H,
+    data: &[u8],
+) -> Result<[u8; D_BYTES]> {
+    let mut output = [0u8; D_BYTES];
+    hasher.update(data)?;
+    hasher.finalize(&mut output)?;
+    Ok(output)
+}
+
+fn digest_bits<const D_BYTES: usize, H: Hasher<D_BYTES> + HasherBits<D_BYTES>>(
+    mut hasher: H,
+    bits: &BitSlice<u8, Lsb0>,
+) -> Result<[u8; D_BYTES]> {
+    let mut output = [0u8; D_BYTES];
+    hasher.update_bits(bits)?;
+    hasher.finalize(&mut output)?;
+    Ok(output)
+}
+
+fn xof<H: XofHasher>(mut hasher: H, data: &[u8], out_len: usize) -> Result<Vec<u8>> {
+    let mut output = vec![0u8; out_len];
+    hasher.update(data)?;
+    hasher.finalize()?;
+    hasher.get_bytes(&mut output, out_len)?;
+    Ok(output)
+}
+
+fn xof_bits<H: XofHasher + XofHasherBits>(
+    mut hasher: H,
+    bits: &BitSlice<u8, Lsb0>,
+    out_len: usize,
+) -> Result<Vec<u8>> {
+    let mut output = vec![0u8; out_len];
+    hasher.update_bits(bits)?;
+    hasher.finalize()?;
+    hasher.get_bytes(&mut output, out_len)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use bitvec::{order::Lsb0, vec::BitVec};
+
+    use super::{
+        AUTO_STREAM_CHUNK_BYTES, AUTO_STREAM_THRESHOLD, auto_hash_256, double_sha3_256,
+        hash_n_bytes, hash_then_expand, rehash_256, sha3_224, sha3_224_bits, sha3_256,
+        sha3_384_bits, sha3_512, shake128, shake128_bits, shake256, shake256_bits,
+    };
+    use crate::{b2h, utils::nist_msg_5};
+
+    fn hex(bytes: &[u8]) -> Result<String> {
+        b2h(&BitVec::<u8, Lsb0>::from_slice(bytes), false, false)
+    }
+
+    #[test]
+    fn test_oneshot_bytes() -> Result<()> {
+        assert_eq!(
+            "6a33e22f20f16642697e8bd549ff7b759252ad56c05a1b0acc31dc69",
+            hex(&sha3_224(b"Hello, world!")?)?
+        );
+        assert_eq!(
+            "8e47f1185ffd014d238fabd02a1a32defe698cbf38c037a90e3c0a0a32370fb52cbd641250508502295fcabcbf676c09470b27443868c8e5f70e26dc337288af",
+            hex(&sha3_512(b"Hello, world!")?)?
+        );
+        assert_eq!("7f9c2ba4", hex(&shake128(b"", 4)?)?);
+        assert_eq!("46b9dd2b", hex(&shake256(b"", 4)?)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_oneshot_bits() -> Result<()> {
+        assert_eq!(
+            "ffbad5da96bad71789330206dc6768ecaeb1b32dca6b3301489674ab",
+            hex(&sha3_224_bits(&nist_msg_5())?)?
+        );
+        assert_eq!(
+            "737c9b491885e9bf7428e792741a7bf8dca9653471c3e148473f2c236b6a0a6455eb1dce9f779b4b6b237fef171b1c64",
+            hex(&sha3_384_bits(&nist_msg_5())?)?
+        );
+        assert_eq!("2e0abfba", hex(&shake128_bits(&nist_msg_5(), 4)?)?);
+        assert_eq!("48a5c11a", hex(&shake256_bits(&nist_msg_5(), 4)?)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_n_bytes() -> Result<()> {
+        assert_eq!(
+            "6a33e22f20f16642697e8bd549ff7b75",
+            hex(&hash_n_bytes(b"Hello, world!", 16)?)?
+        );
+        assert_eq!(
+            "6ba9ea268965916f5937228dde678c202f9fe756a87d8b1b7362869583a45901fd1a27289d72fc0e3ff48b1b78827d3a",
+            hex(&hash_n_bytes(b"Hello, world!", 48)?)?
+        );
+        assert_eq!(
+            "cf68a0d388047ed588ad72d3808cf9a3243f04d4901748c705fbf3a27d955542fd9d53af53e84c8abd4fce6e224af9a0a9e7eea5573a886b1af8c29f9897c8b59efe1b36ca402eec6939370dd8945963d6c81715729e6b5c937af4a8d401a07dde580df9",
+            hex(&hash_n_bytes(b"Hello, world!", 100)?)?
+        );
+        assert_eq!(
+            sha3_512(b"Hello, world!")?.to_vec(),
+            hash_n_bytes(b"Hello, world!", 64)?
+        );
+        assert!(hash_n_bytes(b"Hello, world!", 0)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_then_expand() -> Result<()> {
+        let expanded = hash_then_expand(b"correct horse battery staple", 32)?;
+        assert_eq!(
+            "20b8933b208d17985690609c493985fdced1971eb22517f68cc5a410f09ef69b",
+            hex(&expanded)?
+        );
+        assert_eq!(
+            expanded[..16],
+            hash_then_expand(b"correct horse battery staple", 16)?
+        );
+        assert_eq!(
+            shake256(&sha3_256(b"correct horse battery staple")?, 64)?,
+            hash_then_expand(b"correct horse battery staple", 64)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rehash_256() -> Result<()> {
+        assert_eq!(sha3_256(&sha3_256(b"x")?)?, rehash_256(&sha3_256(b"x")?));
+        assert_eq!(sha3_256(&[0u8; 32])?, rehash_256(&[0u8; 32]));
+        assert_eq!(sha3_256(&[0xffu8; 32])?, rehash_256(&[0xffu8; 32]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_double_sha3_256() -> Result<()> {
+        assert_eq!(sha3_256(&sha3_256(b"x")?)?, double_sha3_256(b"x")?);
+        assert_eq!(sha3_256(&sha3_256(b"")?)?, double_sha3_256(b"")?);
+        assert_ne!(sha3_256(b"x")?, double_sha3_256(b"x")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_auto_hash_256_matches_sha3_256() -> Result<()> {
+        let data = (0..=250).cycle().take(1 << 20).collect::<Vec<u8>>();
+        let sizes = [
+            0,
+            1,
+            135,
+            136,
+            137,
+            AUTO_STREAM_THRESHOLD - 1,
+            AUTO_STREAM_THRESHOLD,
+            AUTO_STREAM_THRESHOLD + 1,
+            AUTO_STREAM_THRESHOLD + AUTO_STREAM_CHUNK_BYTES,
+            1 << 20,
+        ];
+        for size in sizes {
+            assert_eq!(sha3_256(&data[..size])?, auto_hash_256(&data[..size])?);
+        }
+        Ok(())
+    }
+}
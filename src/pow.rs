@@ -0,0 +1,98 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! SHA3-256 proof-of-work verification
+
+use anyhow::Result;
+
+use crate::sha3_256;
+
+/// Check that `nonce` is a proof of work for `data` at a difficulty of
+/// `leading_zero_bits`.
+///
+/// The digest `SHA3-256(data || nonce.to_le_bytes())` must start with at least
+/// `leading_zero_bits` zero bits, counted from the most significant bit of the first byte
+/// and continuing into the following bytes, i.e. the digest read as a big-endian number is
+/// below `2^(256 - leading_zero_bits)`.  A difficulty above 256 can never be met.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::check_pow;
+/// # pub fn main() -> Result<()> {
+/// let mut nonce = 0;
+/// while !check_pow(b"block", nonce, 8)? {
+///     nonce += 1;
+/// }
+/// assert!(check_pow(b"block", nonce, 0)?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if the underlying SHA3-256 operations fail.
+///
+pub fn check_pow(data: &[u8], nonce: u64, leading_zero_bits: usize) -> Result<bool> {
+    let mut input = data.to_vec();
+    input.extend_from_slice(&nonce.to_le_bytes());
+    let digest = sha3_256(&input)?;
+    Ok(count_leading_zero_bits(&digest) >= leading_zero_bits)
+}
+
+// The number of zero bits before the first one bit, from the most significant bit of the
+// first byte
+fn count_leading_zero_bits(bytes: &[u8]) -> usize {
+    let mut count = 0;
+    for byte in bytes {
+        count += byte.leading_zeros() as usize;
+        if *byte != 0 {
+            break;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::{check_pow, count_leading_zero_bits};
+    use crate::sha3_256;
+
+    #[test]
+    fn test_count_leading_zero_bits() {
+        assert_eq!(0, count_leading_zero_bits(&[0x80, 0x00]));
+        assert_eq!(7, count_leading_zero_bits(&[0x01, 0xff]));
+        assert_eq!(8, count_leading_zero_bits(&[0x00, 0x80]));
+        // The count continues across bytes, and stops at the first one bit
+        assert_eq!(12, count_leading_zero_bits(&[0x00, 0x0f, 0x00]));
+        assert_eq!(16, count_leading_zero_bits(&[0x00, 0x00]));
+        assert_eq!(0, count_leading_zero_bits(&[]));
+    }
+
+    #[test]
+    fn test_check_pow() -> Result<()> {
+        let data = b"proof of work";
+        let mut nonce = 0;
+        while !check_pow(data, nonce, 10)? {
+            nonce += 1;
+        }
+
+        let mut input = data.to_vec();
+        input.extend_from_slice(&nonce.to_le_bytes());
+        let digest = sha3_256(&input)?;
+        let zeros = count_leading_zero_bits(&digest);
+        assert!(zeros >= 10);
+        assert_eq!(0, digest[0]);
+
+        assert!(check_pow(data, nonce, 0)?);
+        assert!(check_pow(data, nonce, zeros)?);
+        assert!(!check_pow(data, nonce, zeros + 1)?);
+        assert!(!check_pow(data, nonce, 257)?);
+        Ok(())
+    }
+}
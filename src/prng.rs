@@ -0,0 +1,223 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Deterministic pseudo-random output built on SHAKE256
+
+use anyhow::Result;
+
+use crate::{Shake256, XofHasher};
+
+// The size of the chaining value carried across a reseed
+const CHAINING_BYTES: usize = 64;
+
+/// A reseedable pseudo-random byte generator built on the SHAKE256 sponge.
+///
+/// Output is squeezed from a SHAKE256 sponge that has absorbed the seed.  Calling
+/// [`reseed`](Self::reseed) squeezes a 64 byte chaining value from the current state,
+/// discards that state, and absorbs the chaining value followed by the fresh entropy into a
+/// new sponge, which is then permuted before any further output.  The new state therefore
+/// depends on everything absorbed so far, but output produced before the reseed cannot be
+/// reconstructed from it.
+///
+/// This is a deterministic generator: the same seed and reseed inputs always produce the
+/// same stream.  It is only as unpredictable as the entropy given to it.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::KeccakPrng;
+/// # pub fn main() -> Result<()> {
+/// let mut prng = KeccakPrng::new(b"seed material")?;
+/// let mut buf = [0u8; 16];
+/// prng.fill_bytes(&mut buf)?;
+/// prng.reseed(b"fresh entropy")?;
+/// prng.fill_bytes(&mut buf)?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeccakPrng {
+    xof: Shake256,
+}
+
+impl KeccakPrng {
+    /// Create a new generator from the given seed.
+    ///
+    /// # Errors
+    /// An error will be returned if the seed cannot be absorbed.
+    ///
+    pub fn new(seed: &[u8]) -> Result<Self> {
+        Ok(Self {
+            xof: Self::absorb(&[seed])?,
+        })
+    }
+
+    /// Mix fresh entropy into the generator state.
+    ///
+    /// # Errors
+    /// An error will be returned if the state cannot be squeezed or the entropy cannot be
+    /// absorbed.
+    ///
+    pub fn reseed(&mut self, extra: &[u8]) -> Result<()> {
+        let mut chaining_value = [0u8; CHAINING_BYTES];
+        self.xof.get_bytes(&mut chaining_value, CHAINING_BYTES)?;
+        self.xof = Self::absorb(&[&chaining_value, extra])?;
+        Ok(())
+    }
+
+    /// Fill `dest` with pseudo-random bytes.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying sponge cannot be squeezed.
+    ///
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+        self.xof.get_bytes(dest, dest.len())
+    }
+
+    fn absorb(inputs: &[&[u8]]) -> Result<Shake256> {
+        let mut xof = Shake256::new();
+        for input in inputs {
+            xof.update(input)?;
+        }
+        // finalize permutes the state before any output is squeezed
+        xof.finalize()?;
+        Ok(xof)
+    }
+}
+
+/// Derive a deterministic, random-looking salt of `len` bytes for the given `index`.
+///
+/// The salt is the first `len` bytes of `SHAKE256(seed || index.to_le_bytes())`, so every
+/// `(seed, index)` pair always produces the same bytes.  This is intended for generating
+/// reproducible test fixtures without an RNG; the salts are only as unpredictable as the
+/// seed.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::derive_salt;
+/// # pub fn main() -> Result<()> {
+/// let first = derive_salt(b"fixtures", 0, 16)?;
+/// assert_eq!(first, derive_salt(b"fixtures", 0, 16)?);
+/// assert_ne!(first, derive_salt(b"fixtures", 1, 16)?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if the underlying SHAKE256 operations fail.
+///
+pub fn derive_salt(seed: &[u8], index: u64, len: usize) -> Result<Vec<u8>> {
+    shake256_with_counter(seed, index, len)
+}
+
+/// Derive a deterministic `nonce_len` byte nonce for the given message `counter`.
+///
+/// The nonce is the first `nonce_len` bytes of `SHAKE256(key || counter.to_le_bytes())`,
+/// so each `(key, counter)` pair always produces the same nonce and distinct counters
+/// produce distinct nonces (barring a SHAKE256 collision).  Never reuse a counter with the
+/// same key, e.g. persist the counter across restarts.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::derive_nonce;
+/// # pub fn main() -> Result<()> {
+/// let nonce = derive_nonce(b"session key", 0, 12)?;
+/// assert_eq!(nonce, derive_nonce(b"session key", 0, 12)?);
+/// assert_ne!(nonce, derive_nonce(b"session key", 1, 12)?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if the underlying SHAKE256 operations fail.
+///
+pub fn derive_nonce(key: &[u8], counter: u64, nonce_len: usize) -> Result<Vec<u8>> {
+    shake256_with_counter(key, counter, nonce_len)
+}
+
+// The first `len` bytes of SHAKE256(prefix || counter.to_le_bytes())
+fn shake256_with_counter(prefix: &[u8], counter: u64, len: usize) -> Result<Vec<u8>> {
+    let mut xof = Shake256::new();
+    let mut output = vec![0u8; len];
+    xof.update(prefix)?;
+    xof.update(&counter.to_le_bytes())?;
+    xof.finalize()?;
+    xof.get_bytes(&mut output, len)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use anyhow::Result;
+
+    use super::{KeccakPrng, derive_nonce, derive_salt};
+    use crate::nist_format;
+
+    #[test]
+    fn test_prng_is_deterministic() -> Result<()> {
+        let mut first = KeccakPrng::new(b"seed")?;
+        let mut second = KeccakPrng::new(b"seed")?;
+        let mut a = [0u8; 48];
+        let mut b = [0u8; 48];
+        first.fill_bytes(&mut a)?;
+        second.fill_bytes(&mut b)?;
+        assert_eq!(a, b);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prng_reseed_changes_stream() -> Result<()> {
+        let mut reseeded = KeccakPrng::new(b"seed")?;
+        let mut continued = KeccakPrng::new(b"seed")?;
+        let mut buf = [0u8; 32];
+        reseeded.fill_bytes(&mut buf)?;
+        continued.fill_bytes(&mut buf)?;
+
+        reseeded.reseed(b"fresh entropy")?;
+        let mut after_reseed = [0u8; 32];
+        let mut without_reseed = [0u8; 32];
+        reseeded.fill_bytes(&mut after_reseed)?;
+        continued.fill_bytes(&mut without_reseed)?;
+        assert_ne!(after_reseed, without_reseed);
+
+        let mut other = KeccakPrng::new(b"seed")?;
+        other.fill_bytes(&mut buf)?;
+        other.reseed(b"other entropy")?;
+        let mut other_reseed = [0u8; 32];
+        other.fill_bytes(&mut other_reseed)?;
+        assert_ne!(after_reseed, other_reseed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_derive_salt_is_stable_per_index() -> Result<()> {
+        let salt = derive_salt(b"fixture seed", 0, 16)?;
+        assert_eq!(
+            "FF 9C CE 42 FE 39 EA 55 E7 30 44 6D CF 7A 3F 6C",
+            nist_format(&salt)
+        );
+        assert_ne!(salt, derive_salt(b"fixture seed", 1, 16)?);
+        assert_ne!(salt, derive_salt(b"other seed", 0, 16)?);
+        assert!(derive_salt(b"fixture seed", 0, 0)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_derive_nonce_is_unique_per_counter() -> Result<()> {
+        let nonce = derive_nonce(b"nonce key", 7, 12)?;
+        assert_eq!("3E F7 89 FC 12 D8 30 6D 3F 1D 88 7A", nist_format(&nonce));
+        assert_eq!(nonce, derive_nonce(b"nonce key", 7, 12)?);
+
+        let nonces = (0..100)
+            .map(|counter| derive_nonce(b"nonce key", counter, 12))
+            .collect::<Result<HashSet<Vec<u8>>>>()?;
+        assert_eq!(100, nonces.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Sliding window SHA3-256 digests
+
+use anyhow::Result;
+
+use crate::{Hasher, SHA3_256_BYTES, Sha3_256, Sha3Error};
+
+/// A SHA3-256 digest over the most recent `window_size` bytes of a stream.
+///
+/// Keccak is not a rolling hash, so the digest of a window cannot be updated
+/// incrementally as bytes enter and leave it.  `RollingDigest` keeps the window in a
+/// fixed-size ring buffer and recomputes the digest on demand by absorbing the whole
+/// window into a new hasher; no work is shared between digests, since every window starts
+/// at a different byte.  Pushing a byte is O(1); [`digest`](Self::digest) is
+/// O(`window_size`), so call it only when a window digest is actually needed.
+///
+/// Until `window_size` bytes have been pushed, the window holds every byte seen so far.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{Hasher, RollingDigest, SHA3_256_BYTES, Sha3_256};
+/// # pub fn main() -> Result<()> {
+/// let mut rolling = RollingDigest::new(4)?;
+/// for byte in b"Hello, world!" {
+///     rolling.push(*byte);
+/// }
+///
+/// let mut hasher = Sha3_256::new();
+/// let mut expected = [0u8; SHA3_256_BYTES];
+/// hasher.update(b"rld!")?;
+/// hasher.finalize(&mut expected)?;
+/// assert_eq!(expected, rolling.digest()?);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RollingDigest {
+    ring: Vec<u8>,
+    window_size: usize,
+    // index of the oldest byte once the ring is full
+    head: usize,
+}
+
+impl RollingDigest {
+    /// Create a new rolling digest over windows of `window_size` bytes.
+    ///
+    /// # Errors
+    /// An error will be returned if `window_size` is zero.
+    ///
+    pub fn new(window_size: usize) -> Result<Self> {
+        if window_size == 0 {
+            Err(Sha3Error::InvalidWindowSize(window_size).into())
+        } else {
+            Ok(Self {
+                ring: Vec::with_capacity(window_size),
+                window_size,
+                head: 0,
+            })
+        }
+    }
+
+    /// Push a byte into the window, evicting the oldest byte if the window is full.
+    pub fn push(&mut self, byte: u8) {
+        if self.ring.len() < self.window_size {
+            self.ring.push(byte);
+        } else {
+            self.ring[self.head] = byte;
+            self.head = (self.head + 1) % self.window_size;
+        }
+    }
+
+    /// The number of bytes currently in the window.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Returns `true` if no bytes have been pushed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Compute the SHA3-256 digest of the bytes currently in the window, oldest first.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying SHA3-256 operations fail.
+    ///
+    pub fn digest(&self) -> Result<[u8; SHA3_256_BYTES]> {
+        let (newest, oldest) = self.ring.split_at(self.head);
+        let mut hasher = Sha3_256::new();
+        let mut digest = [0u8; SHA3_256_BYTES];
+        hasher.update(oldest)?;
+        hasher.update(newest)?;
+        hasher.finalize(&mut digest)?;
+        Ok(digest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::RollingDigest;
+    use crate::sha3_256;
+
+    #[test]
+    fn test_rolling_digest_matches_window() -> Result<()> {
+        let stream = b"The quick brown fox jumps over the lazy dog";
+        let window_size = 7;
+        let mut rolling = RollingDigest::new(window_size)?;
+
+        for (i, byte) in stream.iter().enumerate() {
+            rolling.push(*byte);
+            let start = (i + 1).saturating_sub(window_size);
+            assert_eq!(sha3_256(&stream[start..=i])?, rolling.digest()?);
+        }
+        assert_eq!(window_size, rolling.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rolling_digest_empty_window() -> Result<()> {
+        let rolling = RollingDigest::new(3)?;
+        assert!(rolling.is_empty());
+        assert_eq!(sha3_256(b"")?, rolling.digest()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rolling_digest_zero_window_size() {
+        assert!(RollingDigest::new(0).is_err());
+    }
+}
@@ -7,10 +7,13 @@
 // modified, or distributed except according to those terms.
 
 use anyhow::Result;
-use bitvec::{bits, order::Lsb0, slice::BitSlice};
+use bitvec::{bits, order::Lsb0, slice::BitSlice, vec::BitVec};
 
-use crate::{Sha3Error, sponge::Keccak1600Sponge, traits::Sponge};
+use crate::{
+    Midstate, Sha3Error, sp800_185::encoding::left_encode, sponge::Keccak1600Sponge, traits::Sponge,
+};
 
+pub(crate) mod scratch;
 pub(crate) mod sha224;
 pub(crate) mod sha256;
 pub(crate) mod sha384;
@@ -21,9 +24,73 @@ pub(crate) mod sha512;
 struct Sha3<const B: usize> {
     sponge: Keccak1600Sponge,
     finalized: bool,
+    // The domain separation bits appended to the message before padding
+    suffix: &'static BitSlice<u8, Lsb0>,
+}
+
+/// The SHA-3 domain separation bits (`0b01`)
+pub(crate) fn sha3_suffix() -> &'static BitSlice<u8, Lsb0> {
+    bits![static u8, Lsb0; 0, 1]
+}
+
+/// The original Keccak submission appends no domain separation bits, only `pad10*1`
+pub(crate) fn keccak_suffix() -> &'static BitSlice<u8, Lsb0> {
+    BitSlice::empty()
 }
 
 impl<const B: usize> Sha3<B> {
+    // `rate` and `capacity` are in bits
+    pub(crate) fn new(rate: usize, capacity: usize, suffix: &'static BitSlice<u8, Lsb0>) -> Self {
+        Self {
+            sponge: Keccak1600Sponge::new(rate, capacity),
+            finalized: false,
+            suffix,
+        }
+    }
+
+    // `rate` and `capacity` are in bits
+    pub(crate) fn with_buffers(
+        rate: usize,
+        capacity: usize,
+        suffix: &'static BitSlice<u8, Lsb0>,
+        (message, output): (BitVec<u8, Lsb0>, BitVec<u8, Lsb0>),
+    ) -> Self {
+        Self {
+            sponge: Keccak1600Sponge::with_buffers(rate, capacity, message, output),
+            finalized: false,
+            suffix,
+        }
+    }
+
+    pub(crate) fn take_buffers(&mut self) -> (BitVec<u8, Lsb0>, BitVec<u8, Lsb0>) {
+        self.sponge.take_buffers()
+    }
+
+    // `rate` and `capacity` are in bits
+    pub(crate) fn from_midstate(midstate: &Midstate, rate: usize, capacity: usize) -> Result<Self> {
+        if midstate.rate == rate {
+            Ok(Self {
+                sponge: Keccak1600Sponge::from_midstate(midstate, capacity),
+                finalized: false,
+                suffix: midstate.suffix,
+            })
+        } else {
+            Err(Sha3Error::MidstateMismatch(midstate.rate, rate).into())
+        }
+    }
+
+    pub(crate) fn export_midstate(&self) -> Result<Midstate> {
+        if self.finalized {
+            Err(Sha3Error::Finalized.into())
+        } else {
+            self.sponge.export_midstate(self.suffix)
+        }
+    }
+
+    pub(crate) fn optimal_update_size(&self) -> usize {
+        self.sponge.rate_bytes()
+    }
+
     pub(crate) fn update(&mut self, data: &[u8]) -> Result<()> {
         // Update the internal state with the new data
         if self.finalized {
@@ -46,8 +113,8 @@ impl<const B: usize> Sha3<B> {
         if self.finalized {
             Err(Sha3Error::Finalized.into())
         } else {
-            // Append the SHA-3 domain separation bits (0b01) to the message
-            self.sponge.update_bits(bits![u8, Lsb0; 0, 1])?;
+            // Append the domain separation bits (0b01 for SHA-3) to the message
+            self.sponge.append_suffix(self.suffix)?;
             let num_bits = output.len() * 8;
             // Start the absorbing phase
             self.sponge.absorb()?;
@@ -57,4 +124,64 @@ impl<const B: usize> Sha3<B> {
             Ok(())
         }
     }
+
+    #[cfg(feature = "low-level")]
+    pub(crate) fn finalize_state(&mut self) -> Result<[u64; crate::LANE_COUNT]> {
+        if self.finalized {
+            Err(Sha3Error::Finalized.into())
+        } else {
+            self.sponge.append_suffix(self.suffix)?;
+            self.sponge.absorb()?;
+            self.finalized = true;
+            Ok(*self.sponge.state())
+        }
+    }
+
+    /// The number of message bits given to `update` and `update_bits` so far.
+    pub(crate) fn bits_absorbed(&self) -> usize {
+        self.sponge.bits_absorbed()
+    }
+
+    pub(crate) fn finalize_with_length_tag(&mut self, output: &mut [u8; B]) -> Result<Vec<u8>> {
+        let tag = left_encode(self.sponge.bits_absorbed());
+        self.finalize(output)?;
+        Ok(tag)
+    }
+}
+
+/// Check that a midstate exported after a prefix resumes to the same digests as hashing
+/// `prefix || suffix` in full, and that a finalized hasher cannot export one.
+#[cfg(test)]
+pub(crate) fn check_midstate_resume<const D_BYTES: usize, H>(
+    new: fn() -> H,
+    export: fn(&H) -> Result<Midstate>,
+    resume: fn(&Midstate) -> Result<H>,
+) -> Result<()>
+where
+    H: crate::Hasher<D_BYTES> + crate::HasherBits<D_BYTES>,
+{
+    let prefix = (0..=250).cycle().take(200).collect::<Vec<u8>>();
+    let mut hasher = new();
+    hasher.update(&prefix)?;
+    hasher.update_bits(bits![u8, Lsb0; 1, 0, 1])?;
+    let midstate = export(&hasher)?;
+
+    for suffix in [&b"first suffix"[..], &b"second"[..]] {
+        let mut resumed = resume(&midstate)?;
+        let mut result = [0u8; D_BYTES];
+        resumed.update(suffix)?;
+        resumed.finalize(&mut result)?;
+
+        let mut full = new();
+        let mut expected = [0u8; D_BYTES];
+        full.update(&prefix)?;
+        full.update_bits(bits![u8, Lsb0; 1, 0, 1])?;
+        full.update(suffix)?;
+        full.finalize(&mut expected)?;
+        assert_eq!(expected, result);
+    }
+
+    hasher.finalize(&mut [0u8; D_BYTES])?;
+    assert!(export(&hasher).is_err());
+    Ok(())
 }
@@ -0,0 +1,152 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use anyhow::Result;
+use bitvec::{order::Lsb0, slice::BitSlice, vec::BitVec};
+
+use crate::{Hasher, HasherBits, sha3::Sha3};
+
+/// Reusable working buffers for the SHA-3 hashers.
+///
+/// Each hasher buffers the trailing partial block of its message and the squeezed output
+/// bits.  Hashing many short messages through one scratch (see, e.g.,
+/// [`Sha3_256::with_scratch`](crate::Sha3_256::with_scratch)) lets every hash after the
+/// first reuse those allocations instead of making new ones.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{Hasher, Sha3_256, Sha3Scratch};
+/// #
+/// # fn main() -> Result<()> {
+/// let mut scratch = Sha3Scratch::default();
+/// for item in [&b"abc"[..], b"def", b"ghi"] {
+///     let mut hasher = Sha3_256::with_scratch(&mut scratch);
+///     let mut digest = [0u8; 32];
+///     hasher.update(item)?;
+///     hasher.finalize(&mut digest)?;
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Sha3Scratch {
+    message: BitVec<u8, Lsb0>,
+    output: BitVec<u8, Lsb0>,
+}
+
+impl Sha3Scratch {
+    /// Create a new, empty scratch.  The buffers grow on first use.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn take(&mut self) -> (BitVec<u8, Lsb0>, BitVec<u8, Lsb0>) {
+        (
+            std::mem::take(&mut self.message),
+            std::mem::take(&mut self.output),
+        )
+    }
+}
+
+/// A SHA-3 hasher borrowing its working buffers from a [`Sha3Scratch`].
+///
+/// The buffers are handed back to the scratch when the hasher is dropped.
+#[derive(Debug)]
+pub struct ScratchHasher<'a, const B: usize> {
+    inner: Sha3<B>,
+    scratch: &'a mut Sha3Scratch,
+}
+
+impl<'a, const B: usize> ScratchHasher<'a, B> {
+    // `rate` and `capacity` are in bits
+    pub(crate) fn new(
+        scratch: &'a mut Sha3Scratch,
+        rate: usize,
+        capacity: usize,
+        suffix: &'static BitSlice<u8, Lsb0>,
+    ) -> Self {
+        Self {
+            inner: Sha3::with_buffers(rate, capacity, suffix, scratch.take()),
+            scratch,
+        }
+    }
+}
+
+impl<const B: usize> Drop for ScratchHasher<'_, B> {
+    fn drop(&mut self) {
+        let (message, output) = self.inner.take_buffers();
+        self.scratch.message = message;
+        self.scratch.output = output;
+    }
+}
+
+impl<const B: usize> Hasher<B> for ScratchHasher<'_, B> {
+    fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.inner.update(data)
+    }
+
+    fn finalize(&mut self, output: &mut [u8; B]) -> Result<()> {
+        self.inner.finalize(output)
+    }
+}
+
+impl<const B: usize> HasherBits<B> for ScratchHasher<'_, B> {
+    fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()> {
+        self.inner.update_bits(data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Sha3Scratch;
+    use crate::{Hasher, HasherBits, Sha3_224, Sha3_256, Sha3_512, utils::nist_msg_5};
+    use anyhow::Result;
+
+    #[test]
+    fn scratch_matches_standard_hashers() -> Result<()> {
+        let mut scratch = Sha3Scratch::new();
+        for len in [0usize, 1, 135, 136, 137, 500] {
+            let data = vec![0xa3u8; len];
+
+            let mut expected = [0u8; 32];
+            let mut hasher = Sha3_256::new();
+            hasher.update(&data)?;
+            hasher.finalize(&mut expected)?;
+
+            let mut actual = [0u8; 32];
+            let mut hasher = Sha3_256::with_scratch(&mut scratch);
+            hasher.update(&data)?;
+            hasher.finalize(&mut actual)?;
+            assert_eq!(expected, actual);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn scratch_is_shared_across_variants() -> Result<()> {
+        let mut scratch = Sha3Scratch::new();
+
+        let mut expected = [0u8; 64];
+        let mut hasher = Sha3_512::new();
+        hasher.update(b"abc")?;
+        hasher.finalize(&mut expected)?;
+
+        // Leave stale bits in the scratch from a different rate and an unfinished hash
+        let mut hasher = Sha3_224::with_scratch(&mut scratch);
+        hasher.update_bits(&nist_msg_5())?;
+        drop(hasher);
+
+        let mut actual = [0u8; 64];
+        let mut hasher = Sha3_512::with_scratch(&mut scratch);
+        hasher.update(b"abc")?;
+        hasher.finalize(&mut actual)?;
+        assert_eq!(expected, actual);
+        Ok(())
+    }
+}
@@ -10,10 +10,9 @@ use anyhow::Result;
 use bitvec::{order::Lsb0, slice::BitSlice};
 
 use crate::{
-    Hasher, HasherBits,
+    DynHasher, Hasher, HasherBits, Midstate, ScratchHasher, Sha3Error, Sha3Scratch,
     constants::{SHA3_256_BYTES, SHA3_256_CAPACITY, SHA3_256_RATE},
-    sha3::Sha3,
-    sponge::Keccak1600Sponge,
+    sha3::{Sha3, keccak_suffix, sha3_suffix},
 };
 
 /// SHA3-256 hash function (`SHA3-256(M) = KECCAK[512](M||01, 256)`)
@@ -33,12 +32,114 @@ impl Sha3_256 {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            inner: Sha3::<{ SHA3_256_BYTES }> {
-                sponge: Keccak1600Sponge::new(SHA3_256_RATE, SHA3_256_CAPACITY),
-                finalized: false,
-            },
+            inner: Sha3::new(SHA3_256_RATE, SHA3_256_CAPACITY, sha3_suffix()),
         }
     }
+
+    /// Create a new hasher for the original (pre-FIPS 202) Keccak-256.
+    ///
+    /// Keccak-256 uses the same sponge parameters as SHA3-256, but appends no domain
+    /// separation bits before the `pad10*1` padding.  This is the variant used by, e.g.,
+    /// Ethereum, and its digests differ from SHA3-256 for every input.
+    #[must_use]
+    pub fn new_keccak_legacy() -> Self {
+        Self {
+            inner: Sha3::new(SHA3_256_RATE, SHA3_256_CAPACITY, keccak_suffix()),
+        }
+    }
+
+    /// Create a new SHA3-256 hasher that borrows its working buffers from `scratch`.
+    ///
+    /// Reusing one [`Sha3Scratch`] across many hashes avoids allocating new buffers for
+    /// each one.  The buffers are returned to the scratch when the hasher is dropped.
+    #[must_use]
+    pub fn with_scratch(scratch: &mut Sha3Scratch) -> ScratchHasher<'_, { SHA3_256_BYTES }> {
+        ScratchHasher::new(scratch, SHA3_256_RATE, SHA3_256_CAPACITY, sha3_suffix())
+    }
+
+    /// Finalize the hash computation, writing the digest to `output`, and return the
+    /// canonical encoding of the message length that was hashed.
+    ///
+    /// The returned tag is `left_encode(n)` (SP 800-185) where `n` is the total number of
+    /// message bits given to `update` and `update_bits`, so a verifier can tell exactly how
+    /// many bits were hashed (e.g. a 5-bit message from a byte-aligned one).
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    pub fn finalize_with_length_tag(
+        &mut self,
+        output: &mut [u8; SHA3_256_BYTES],
+    ) -> Result<Vec<u8>> {
+        self.inner.finalize_with_length_tag(output)
+    }
+
+    /// Finalize the hash computation and return the number of bytes hashed along with the
+    /// digest, for framed protocols whose headers carry both.
+    ///
+    /// The length is the total number of message bytes given to `update` and
+    /// `update_bits`.  The checks happen before finalizing, so on a length error the hasher
+    /// is left as it was.
+    ///
+    /// # Errors
+    /// An error will be returned if the message is not a whole number of bytes, if it is
+    /// longer than `u32::MAX` bytes, or if the hasher has already been finalized.
+    ///
+    pub fn finalize_framed(&mut self) -> Result<(u32, [u8; SHA3_256_BYTES])> {
+        let bits = self.inner.bits_absorbed();
+        if bits % 8 != 0 {
+            return Err(Sha3Error::NonByteAligned(bits).into());
+        }
+        let len =
+            u32::try_from(bits / 8).map_err(|_| Sha3Error::InputTooLarge(u32::MAX as usize))?;
+        let mut digest = [0u8; SHA3_256_BYTES];
+        self.inner.finalize(&mut digest)?;
+        Ok((len, digest))
+    }
+
+    /// Resume a hasher from a midstate exported by [`export_midstate`](Self::export_midstate).
+    ///
+    /// The resumed hasher behaves exactly like the hasher the midstate was exported from,
+    /// including its domain separation (SHA3-256 or Keccak-256).
+    ///
+    /// # Errors
+    /// An error will be returned if the midstate was not exported from a SHA3-256 or
+    /// Keccak-256 hasher.
+    ///
+    pub fn from_midstate(midstate: &Midstate) -> Result<Self> {
+        Ok(Self {
+            inner: Sha3::from_midstate(midstate, SHA3_256_RATE, SHA3_256_CAPACITY)?,
+        })
+    }
+
+    /// Export the current state as a [`Midstate`] that can be resumed any number of times
+    /// with [`from_midstate`](Self::from_midstate).
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    pub fn export_midstate(&self) -> Result<Midstate> {
+        self.inner.export_midstate()
+    }
+
+    /// Finalize the hash computation and return the full Keccak-f\[1600\] state after the
+    /// message has been padded and absorbed, instead of the truncated digest.
+    ///
+    /// The digest is the first 32 bytes of the little-endian lanes of this state.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    #[cfg(feature = "low-level")]
+    pub fn finalize_state(&mut self) -> Result<[u64; crate::LANE_COUNT]> {
+        self.inner.finalize_state()
+    }
+
+    /// The SHA3-256 rate, 136 bytes.
+    #[must_use]
+    pub fn optimal_update_size(&self) -> usize {
+        self.inner.optimal_update_size()
+    }
 }
 
 impl Hasher<{ SHA3_256_BYTES }> for Sha3_256 {
@@ -51,6 +152,22 @@ impl Hasher<{ SHA3_256_BYTES }> for Sha3_256 {
     }
 }
 
+impl DynHasher for Sha3_256 {
+    fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.inner.update(data)
+    }
+
+    fn finalize_to_vec(&mut self) -> Result<Vec<u8>> {
+        let mut output = [0u8; SHA3_256_BYTES];
+        self.inner.finalize(&mut output)?;
+        Ok(output.to_vec())
+    }
+
+    fn output_len(&self) -> usize {
+        SHA3_256_BYTES
+    }
+}
+
 impl HasherBits<{ SHA3_256_BYTES }> for Sha3_256 {
     fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()> {
         self.inner.update_bits(data)
@@ -63,9 +180,13 @@ mod test {
     use bitvec::{bits, order::Lsb0, vec::BitVec};
 
     use crate::{
-        Hasher, HasherBits, Sha3_256, b2h,
+        Hasher, HasherBits, Sha3_256, Sha3_512, b2h,
         constants::SHA3_256_BYTES,
+        nist_format,
+        sha3::check_midstate_resume,
+        sp800_185::encoding::left_encode,
         test::{Mode, create_test_vector},
+        utils::{nist_msg_5, nist_msg_30},
     };
 
     const SHA3_256_0_BITS: &str = "A7 FF C6 F8 BF 1E D7 66 51 C1 47 56 A0 61 D6 62 F5 80 FF 4D E4 3B 49 FA 82 D8 0A 4B 80 F8 43 4A";
@@ -81,7 +202,7 @@ mod test {
         let mut hasher = Sha3_256::new();
         let mut result = [0u8; SHA3_256_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_256_0_BITS, res);
         Ok(())
     }
@@ -105,10 +226,10 @@ mod test {
     /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/SHA3-256_Msg5.pdf>
     fn test_sha3_256_5_bits() -> Result<()> {
         let mut hasher = Sha3_256::default();
-        hasher.update_bits(bits![u8, Lsb0; 1, 1, 0, 0, 1])?;
+        hasher.update_bits(&nist_msg_5())?;
         let mut result = [0u8; SHA3_256_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_256_5_BITS, res);
         Ok(())
     }
@@ -117,10 +238,10 @@ mod test {
     /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/SHA3-256_Msg30.pdf>
     fn test_sha3_256_30_bits() -> Result<()> {
         let mut hasher = Sha3_256::new();
-        hasher.update_bits(bits![u8, Lsb0; 1, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 1, 0, 1, 0, 1, 1, 0, 1, 1, 1, 1, 0, 1, 0, 0, 1, 1, 0])?;
+        hasher.update_bits(&nist_msg_30())?;
         let mut result = [0u8; SHA3_256_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_256_30_BITS, res);
         Ok(())
     }
@@ -135,7 +256,7 @@ mod test {
         hasher.update_bits(bit_vec.as_bitslice())?;
         let mut result = [0u8; SHA3_256_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_256_1600_BITS, res);
         Ok(())
     }
@@ -150,7 +271,7 @@ mod test {
         hasher.update_bits(bit_vec.as_bitslice())?;
         let mut result = [0u8; SHA3_256_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_256_1605_BITS, res);
         Ok(())
     }
@@ -165,7 +286,7 @@ mod test {
         hasher.update_bits(bit_vec.as_bitslice())?;
         let mut result = [0u8; SHA3_256_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_256_1630_BITS, res);
         Ok(())
     }
@@ -188,4 +309,108 @@ mod test {
         assert!(hasher.finalize(&mut [0u8; SHA3_256_BYTES]).is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_sha3_256_finalize_with_length_tag() -> Result<()> {
+        let mut hasher = Sha3_256::new();
+        hasher.update_bits(&nist_msg_30())?;
+        let mut result = [0u8; SHA3_256_BYTES];
+        let tag = hasher.finalize_with_length_tag(&mut result)?;
+        assert_eq!(left_encode(30), tag);
+        let res = nist_format(&result);
+        assert_eq!(SHA3_256_30_BITS, res);
+
+        let mut hasher = Sha3_256::new();
+        hasher.update(b"Hello, ")?;
+        hasher.update(b"world!")?;
+        let tag = hasher.finalize_with_length_tag(&mut result)?;
+        assert_eq!(vec![0x01, 0x68], tag);
+        assert!(hasher.finalize_with_length_tag(&mut result).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sha3_256_finalize_framed() -> Result<()> {
+        let mut hasher = Sha3_256::new();
+        hasher.update(b"Hello, ")?;
+        hasher.update(b"world!")?;
+        let (len, digest) = hasher.finalize_framed()?;
+        assert_eq!(13, len);
+        assert_eq!(
+            "F3 45 A2 19 DA 00 5E BE 9C 1A 1E AA D9 7B BF 38 A1 0C 84 73 E4 1D 0A F7 FB 61 7C AA 0C 6A A7 22",
+            nist_format(&digest)
+        );
+        assert!(hasher.finalize_framed().is_err());
+
+        let (len, digest) = Sha3_256::new().finalize_framed()?;
+        assert_eq!(0, len);
+        assert_eq!(0xa7, digest[0]);
+
+        // A partial byte has no byte length, and the hasher is not finalized
+        let mut hasher = Sha3_256::new();
+        hasher.update_bits(&nist_msg_30())?;
+        assert!(hasher.finalize_framed().is_err());
+        let mut result = [0u8; SHA3_256_BYTES];
+        hasher.finalize(&mut result)?;
+        assert_eq!(SHA3_256_30_BITS, nist_format(&result));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sha3_256_keccak_legacy_0_bits() -> Result<()> {
+        let mut hasher = Sha3_256::new_keccak_legacy();
+        let mut result = [0u8; SHA3_256_BYTES];
+        hasher.finalize(&mut result)?;
+        let res = b2h(&BitVec::from_slice(&result), false, false)?;
+        assert_eq!(
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            res
+        );
+
+        let mut hasher = Sha3_256::new();
+        hasher.finalize(&mut result)?;
+        let res = nist_format(&result);
+        assert_eq!(SHA3_256_0_BITS, res);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sha3_256_midstate_resume() -> Result<()> {
+        check_midstate_resume(
+            Sha3_256::new,
+            Sha3_256::export_midstate,
+            Sha3_256::from_midstate,
+        )?;
+
+        let mut hasher = Sha3_256::new();
+        hasher.update(b"prefix")?;
+        assert!(Sha3_512::from_midstate(&hasher.export_midstate()?).is_err());
+
+        let mut legacy = Sha3_256::new_keccak_legacy();
+        let mut expected = [0u8; SHA3_256_BYTES];
+        let mut result = [0u8; SHA3_256_BYTES];
+        let mut resumed = Sha3_256::from_midstate(&legacy.export_midstate()?)?;
+        legacy.finalize(&mut expected)?;
+        resumed.finalize(&mut result)?;
+        assert_eq!(expected, result);
+        Ok(())
+    }
+
+    #[cfg(feature = "low-level")]
+    #[test]
+    fn test_sha3_256_finalize_state() -> Result<()> {
+        let mut hasher = Sha3_256::new();
+        let state = hasher.finalize_state()?;
+        assert_eq!(0x66D7_1EBF_F8C6_FFA7, state[0]);
+
+        let mut expected = [0u8; SHA3_256_BYTES];
+        Sha3_256::new().finalize(&mut expected)?;
+        let digest = state[..4]
+            .iter()
+            .flat_map(|lane| lane.to_le_bytes())
+            .collect::<Vec<u8>>();
+        assert_eq!(expected.as_slice(), digest);
+        assert!(hasher.finalize_state().is_err());
+        Ok(())
+    }
 }
@@ -10,10 +10,9 @@ use anyhow::Result;
 use bitvec::{order::Lsb0, slice::BitSlice};
 
 use crate::{
-    Hasher, HasherBits,
+    DynHasher, Hasher, HasherBits, Midstate, ScratchHasher, Sha3Scratch,
     constants::{SHA3_384_BYTES, SHA3_384_CAPACITY, SHA3_384_RATE},
-    sha3::Sha3,
-    sponge::Keccak1600Sponge,
+    sha3::{Sha3, sha3_suffix},
 };
 
 /// SHA3-384 hash function (`SHA3-384(M) = KECCAK[768](M||01, 384)`)
@@ -33,12 +32,77 @@ impl Sha3_384 {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            inner: Sha3::<{ SHA3_384_BYTES }> {
-                sponge: Keccak1600Sponge::new(SHA3_384_RATE, SHA3_384_CAPACITY),
-                finalized: false,
-            },
+            inner: Sha3::new(SHA3_384_RATE, SHA3_384_CAPACITY, sha3_suffix()),
         }
     }
+
+    /// Create a new SHA3-384 hasher that borrows its working buffers from `scratch`.
+    ///
+    /// Reusing one [`Sha3Scratch`] across many hashes avoids allocating new buffers for
+    /// each one.  The buffers are returned to the scratch when the hasher is dropped.
+    #[must_use]
+    pub fn with_scratch(scratch: &mut Sha3Scratch) -> ScratchHasher<'_, { SHA3_384_BYTES }> {
+        ScratchHasher::new(scratch, SHA3_384_RATE, SHA3_384_CAPACITY, sha3_suffix())
+    }
+
+    /// Finalize the hash computation, writing the digest to `output`, and return the
+    /// canonical encoding of the message length that was hashed.
+    ///
+    /// The returned tag is `left_encode(n)` (SP 800-185) where `n` is the total number of
+    /// message bits given to `update` and `update_bits`, so a verifier can tell exactly how
+    /// many bits were hashed (e.g. a 5-bit message from a byte-aligned one).
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    pub fn finalize_with_length_tag(
+        &mut self,
+        output: &mut [u8; SHA3_384_BYTES],
+    ) -> Result<Vec<u8>> {
+        self.inner.finalize_with_length_tag(output)
+    }
+
+    /// Resume a hasher from a midstate exported by [`export_midstate`](Self::export_midstate).
+    ///
+    /// The resumed hasher behaves exactly like the hasher the midstate was exported from.
+    ///
+    /// # Errors
+    /// An error will be returned if the midstate was not exported from a SHA3-384 hasher.
+    ///
+    pub fn from_midstate(midstate: &Midstate) -> Result<Self> {
+        Ok(Self {
+            inner: Sha3::from_midstate(midstate, SHA3_384_RATE, SHA3_384_CAPACITY)?,
+        })
+    }
+
+    /// Export the current state as a [`Midstate`] that can be resumed any number of times
+    /// with [`from_midstate`](Self::from_midstate).
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    pub fn export_midstate(&self) -> Result<Midstate> {
+        self.inner.export_midstate()
+    }
+
+    /// Finalize the hash computation and return the full Keccak-f\[1600\] state after the
+    /// message has been padded and absorbed, instead of the truncated digest.
+    ///
+    /// The digest is the first 48 bytes of the little-endian lanes of this state.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    #[cfg(feature = "low-level")]
+    pub fn finalize_state(&mut self) -> Result<[u64; crate::LANE_COUNT]> {
+        self.inner.finalize_state()
+    }
+
+    /// The SHA3-384 rate, 104 bytes.
+    #[must_use]
+    pub fn optimal_update_size(&self) -> usize {
+        self.inner.optimal_update_size()
+    }
 }
 
 impl Hasher<{ SHA3_384_BYTES }> for Sha3_384 {
@@ -51,6 +115,22 @@ impl Hasher<{ SHA3_384_BYTES }> for Sha3_384 {
     }
 }
 
+impl DynHasher for Sha3_384 {
+    fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.inner.update(data)
+    }
+
+    fn finalize_to_vec(&mut self) -> Result<Vec<u8>> {
+        let mut output = [0u8; SHA3_384_BYTES];
+        self.inner.finalize(&mut output)?;
+        Ok(output.to_vec())
+    }
+
+    fn output_len(&self) -> usize {
+        SHA3_384_BYTES
+    }
+}
+
 impl HasherBits<{ SHA3_384_BYTES }> for Sha3_384 {
     fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()> {
         self.inner.update_bits(data)
@@ -65,7 +145,11 @@ mod test {
     use crate::{
         Hasher, HasherBits, Sha3_384, b2h,
         constants::SHA3_384_BYTES,
+        nist_format,
+        sha3::check_midstate_resume,
+        sp800_185::encoding::left_encode,
         test::{Mode, create_test_vector},
+        utils::{nist_msg_5, nist_msg_30},
     };
 
     const SHA3_384_0_BITS: &str = "0C 63 A7 5B 84 5E 4F 7D 01 10 7D 85 2E 4C 24 85 C5 1A 50 AA AA 94 FC 61 99 5E 71 BB EE 98 3A 2A C3 71 38 31 26 4A DB 47 FB 6B D1 E0 58 D5 F0 04";
@@ -81,7 +165,7 @@ mod test {
         let mut hasher = Sha3_384::new();
         let mut result = [0u8; SHA3_384_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_384_0_BITS, res);
         Ok(())
     }
@@ -105,10 +189,10 @@ mod test {
     /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/SHA3-384_Msg5.pdf>
     fn test_sha3_384_5_bits() -> Result<()> {
         let mut hasher = Sha3_384::default();
-        hasher.update_bits(bits![u8, Lsb0; 1, 1, 0, 0, 1])?;
+        hasher.update_bits(&nist_msg_5())?;
         let mut result = [0u8; SHA3_384_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_384_5_BITS, res);
         Ok(())
     }
@@ -117,10 +201,10 @@ mod test {
     /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/SHA3-384_Msg30.pdf>
     fn test_sha3_384_30_bits() -> Result<()> {
         let mut hasher = Sha3_384::new();
-        hasher.update_bits(bits![u8, Lsb0; 1, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 1, 0, 1, 0, 1, 1, 0, 1, 1, 1, 1, 0, 1, 0, 0, 1, 1, 0])?;
+        hasher.update_bits(&nist_msg_30())?;
         let mut result = [0u8; SHA3_384_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_384_30_BITS, res);
         Ok(())
     }
@@ -135,7 +219,7 @@ mod test {
         hasher.update_bits(bit_vec.as_bitslice())?;
         let mut result = [0u8; SHA3_384_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_384_1600_BITS, res);
         Ok(())
     }
@@ -150,7 +234,7 @@ mod test {
         hasher.update_bits(bit_vec.as_bitslice())?;
         let mut result = [0u8; SHA3_384_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_384_1605_BITS, res);
         Ok(())
     }
@@ -165,7 +249,7 @@ mod test {
         hasher.update_bits(bit_vec.as_bitslice())?;
         let mut result = [0u8; SHA3_384_BYTES];
         hasher.finalize(&mut result)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHA3_384_1630_BITS, res);
         Ok(())
     }
@@ -188,4 +272,32 @@ mod test {
         assert!(hasher.finalize(&mut [0u8; SHA3_384_BYTES]).is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_sha3_384_finalize_with_length_tag() -> Result<()> {
+        let mut hasher = Sha3_384::new();
+        hasher.update_bits(&nist_msg_30())?;
+        let mut result = [0u8; SHA3_384_BYTES];
+        let tag = hasher.finalize_with_length_tag(&mut result)?;
+        assert_eq!(left_encode(30), tag);
+        let res = nist_format(&result);
+        assert_eq!(SHA3_384_30_BITS, res);
+
+        let mut hasher = Sha3_384::new();
+        hasher.update(b"Hello, ")?;
+        hasher.update(b"world!")?;
+        let tag = hasher.finalize_with_length_tag(&mut result)?;
+        assert_eq!(vec![0x01, 0x68], tag);
+        assert!(hasher.finalize_with_length_tag(&mut result).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sha3_384_midstate_resume() -> Result<()> {
+        check_midstate_resume(
+            Sha3_384::new,
+            Sha3_384::export_midstate,
+            Sha3_384::from_midstate,
+        )
+    }
 }
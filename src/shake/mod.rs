@@ -9,22 +9,173 @@
 use anyhow::Result;
 use bitvec::{bits, order::Lsb0, slice::BitSlice, vec::BitVec};
 
-use crate::{Sha3Error, sponge::Keccak1600Sponge, traits::Sponge};
+use crate::{
+    DynXof, Sha3Error, Shake128, Shake256, XofHasher,
+    constants::{LANE_COUNT, SHAKE_128_RATE, SHAKE_256_RATE},
+    sponge::Keccak1600Sponge,
+    traits::Sponge,
+};
 
 pub(crate) mod shake128;
 pub(crate) mod shake256;
 
+// The size of a squeeze token: the state lanes followed by the buffered output bit count
+const SQUEEZE_TOKEN_BYTES: usize = (LANE_COUNT + 1) * 8;
+
+/// The SHAKE domain separation bits (`1111`)
+pub(crate) fn shake_suffix() -> &'static BitSlice<u8, Lsb0> {
+    bits![static u8, Lsb0; 1, 1, 1, 1]
+}
+
+/// Select the SHAKE function for the requested security level at runtime.
+///
+/// Returns SHAKE128 for up to 128 bits of security and SHAKE256 for up to 256 bits.
+///
+/// # Errors
+/// An error will be returned if more than 256 bits of security are requested.
+///
+pub fn shake_for_security(bits: usize) -> Result<Box<dyn DynXof>> {
+    match bits {
+        0..=128 => Ok(Box::new(Shake128::new())),
+        129..=256 => Ok(Box::new(Shake256::new())),
+        _ => Err(Sha3Error::InvalidSecurityLevel(bits).into()),
+    }
+}
+
+/// Fill `out` with bytes taken alternately from two finalized SHAKE256 streams, starting
+/// with `a`: `A0, B0, A1, B1, ...`.
+///
+/// `a` supplies `out.len().div_ceil(2)` bytes and `b` supplies `out.len() / 2`, so for an
+/// odd length `a` has squeezed one byte more than `b` when this returns, and a further call
+/// starts with `a` again rather than continuing the alternation.
+///
+/// # Errors
+/// An error will be returned if squeezing either stream fails.
+///
+pub fn interleave_xof(a: &mut Shake256, b: &mut Shake256, out: &mut [u8]) -> Result<()> {
+    let (a_len, b_len) = (out.len().div_ceil(2), out.len() / 2);
+    let mut a_bytes = vec![0u8; a_len];
+    let mut b_bytes = vec![0u8; b_len];
+    a.get_bytes(&mut a_bytes, a_len)?;
+    b.get_bytes(&mut b_bytes, b_len)?;
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = if i % 2 == 0 {
+            a_bytes[i / 2]
+        } else {
+            b_bytes[i / 2]
+        };
+    }
+    Ok(())
+}
+
+/// Find the index of the first byte at which two finalized SHAKE256 streams differ, looking
+/// at no more than `max_bytes` bytes of each.
+///
+/// Returns `None` if the streams agree on all `max_bytes` bytes.  The streams are compared
+/// in rate sized (136 byte) blocks, so both are advanced by the same amount: the block
+/// containing the divergence, or all `max_bytes` bytes if there is none.
+///
+/// This is a debugging aid for interop mismatches, e.g. to see whether two derivations
+/// produce unrelated output or only disagree after a shared prefix.
+///
+/// # Errors
+/// An error will be returned if either stream has not been finalized, or if squeezing
+/// either stream fails.
+///
+pub fn first_divergence(
+    a: &mut Shake256,
+    b: &mut Shake256,
+    max_bytes: usize,
+) -> Result<Option<usize>> {
+    if !a.finalized() || !b.finalized() {
+        return Err(Sha3Error::NotFinalized.into());
+    }
+    let mut a_block = [0u8; SHAKE_256_RATE / 8];
+    let mut b_block = [0u8; SHAKE_256_RATE / 8];
+    let mut offset = 0;
+    while offset < max_bytes {
+        let len = (max_bytes - offset).min(a_block.len());
+        a.get_bytes(&mut a_block[..len], len)?;
+        b.get_bytes(&mut b_block[..len], len)?;
+        if let Some(index) = a_block[..len]
+            .iter()
+            .zip(&b_block[..len])
+            .position(|(x, y)| x != y)
+        {
+            return Ok(Some(offset + index));
+        }
+        offset += len;
+    }
+    Ok(None)
+}
+
 /// SHA-3 XOF hash functions (SHAKE128 and SHAKE256)
 #[derive(Clone, Debug)]
-struct Shake {
+pub(crate) struct Shake {
     sponge: Keccak1600Sponge,
+    // Domain separation bits appended to the message on finalize
+    suffix: &'static BitSlice<u8, Lsb0>,
 }
 
 impl Shake {
+    // `rate` and `capacity` are in bits
+    pub(crate) fn new(rate: usize, capacity: usize, suffix: &'static BitSlice<u8, Lsb0>) -> Self {
+        Self {
+            sponge: Keccak1600Sponge::new(rate, capacity),
+            suffix,
+        }
+    }
+
     pub(crate) fn finalized(&self) -> bool {
         self.sponge.finalized()
     }
 
+    /// Serialize the squeeze position: the state lanes, then the number of buffered output
+    /// bits, all as little-endian `u64`s.
+    pub(crate) fn squeeze_token(&self) -> Result<Vec<u8>> {
+        let (state, buffered) = self.sponge.squeeze_position()?;
+        let mut token = Vec::with_capacity(SQUEEZE_TOKEN_BYTES);
+        for lane in state {
+            token.extend_from_slice(&lane.to_le_bytes());
+        }
+        token.extend_from_slice(&(buffered as u64).to_le_bytes());
+        Ok(token)
+    }
+
+    /// Resume a squeeze from a token created by [`squeeze_token`](Self::squeeze_token).
+    pub(crate) fn from_squeeze_token(
+        rate: usize,
+        capacity: usize,
+        suffix: &'static BitSlice<u8, Lsb0>,
+        token: &[u8],
+    ) -> Result<Self> {
+        if token.len() != SQUEEZE_TOKEN_BYTES {
+            return Err(Sha3Error::InvalidSqueezeToken.into());
+        }
+        let mut words = token.chunks_exact(8).map(|chunk| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            u64::from_le_bytes(bytes)
+        });
+        let mut state = [0u64; LANE_COUNT];
+        for (lane, word) in state.iter_mut().zip(words.by_ref()) {
+            *lane = word;
+        }
+        let buffered = words
+            .next()
+            .and_then(|word| usize::try_from(word).ok())
+            .filter(|&buffered| buffered <= rate)
+            .ok_or(Sha3Error::InvalidSqueezeToken)?;
+        Ok(Self {
+            sponge: Keccak1600Sponge::from_squeeze_position(rate, capacity, state, buffered),
+            suffix,
+        })
+    }
+
+    pub(crate) fn optimal_update_size(&self) -> usize {
+        self.sponge.rate_bytes()
+    }
+
     pub(crate) fn update(&mut self, data: &[u8]) -> Result<()> {
         // Update the internal state with the new data
         self.sponge.update(data)
@@ -39,8 +190,8 @@ impl Shake {
         if self.sponge.finalized() {
             Err(Sha3Error::Finalized.into())
         } else {
-            // Append the SHAKE domain separation bits (0b1111) to the message
-            self.sponge.update_bits(bits![u8, Lsb0; 1, 1, 1, 1])?;
+            // Append the domain separation bits (0b1111 for SHAKE) to the message
+            self.sponge.append_suffix(self.suffix)?;
             // Start the absorbing phase
             self.sponge.absorb()?;
             Ok(())
@@ -49,7 +200,27 @@ impl Shake {
 
     pub(crate) fn get_bytes(&mut self, output: &mut [u8], num_bytes: usize) -> Result<()> {
         // Start the squeezing phase
-        self.sponge.squeeze(output, num_bytes * 8)?;
+        let num_bits = num_bytes
+            .checked_mul(8)
+            .ok_or(Sha3Error::OutputLengthMismatch(output.len(), num_bytes))?;
+        self.sponge.squeeze(output, num_bits)?;
+        Ok(())
+    }
+
+    pub(crate) fn squeeze_with<F>(&mut self, num_bytes: usize, mut f: F) -> Result<()>
+    where
+        F: FnMut(&[u8]) -> Result<()>,
+    {
+        // Large enough for a block of either SHAKE function
+        let mut block = [0u8; SHAKE_128_RATE / 8];
+        let rate_bytes = self.sponge.rate_bytes();
+        let mut remaining = num_bytes;
+        while remaining > 0 {
+            let len = remaining.min(rate_bytes);
+            self.get_bytes(&mut block[..len], len)?;
+            f(&block[..len])?;
+            remaining -= len;
+        }
         Ok(())
     }
 
@@ -12,8 +12,8 @@ use bitvec::{order::Lsb0, slice::BitSlice, vec::BitVec};
 use crate::{
     XofHasher, XofHasherBits,
     constants::{SHAKE_128_CAPACITY, SHAKE_128_RATE},
-    shake::Shake,
-    sponge::Keccak1600Sponge,
+    shake::{Shake, shake_suffix},
+    traits,
 };
 
 /// SHAKE128 XOF function (`SHAKE128(M, d) = KECCAK[256](M||1111, d)`)
@@ -27,11 +27,99 @@ impl Shake128 {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            inner: Shake {
-                sponge: Keccak1600Sponge::new(SHAKE_128_RATE, SHAKE_128_CAPACITY),
-            },
+            inner: Shake::new(SHAKE_128_RATE, SHAKE_128_CAPACITY, shake_suffix()),
         }
     }
+
+    /// Finalize the hasher and convert it into a [`Shake128Xof`] that can only squeeze.
+    ///
+    /// The returned type has no `update`, so absorbing after the absorb phase has ended
+    /// is a compile error rather than a runtime [`Finalized`](crate::Sha3Error::Finalized)
+    /// error.  If the hasher has already been finalized, the squeeze continues from where
+    /// it left off.
+    ///
+    /// ```compile_fail
+    /// # use anyhow::Result;
+    /// # use shashasha::{Shake128, XofHasher};
+    /// # pub fn main() -> Result<()> {
+    /// let mut xof = Shake128::new().into_xof()?;
+    /// xof.update(b"too late")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// An error will be returned if the message cannot be padded and absorbed.
+    ///
+    pub fn into_xof(mut self) -> Result<Shake128Xof> {
+        if !self.inner.finalized() {
+            self.inner.finalize()?;
+        }
+        Ok(Shake128Xof { inner: self.inner })
+    }
+
+    /// Squeeze `num_bytes` bytes, passing them to `f` in chunks of at most the rate
+    /// (168 bytes) rather than buffering the whole output.
+    ///
+    /// The chunks are delivered in order and their concatenation is exactly what
+    /// [`get_bytes`](XofHasher::get_bytes) would have produced; only the final chunk may
+    /// be shorter than the rate.  The hasher must be finalized first.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying squeeze fails, and any error returned
+    /// by `f` stops the squeeze and is passed through.
+    ///
+    pub fn squeeze_with<F>(&mut self, num_bytes: usize, f: F) -> Result<()>
+    where
+        F: FnMut(&[u8]) -> Result<()>,
+    {
+        self.inner.squeeze_with(num_bytes, f)
+    }
+
+    /// The SHAKE128 rate, 168 bytes.
+    #[must_use]
+    pub fn optimal_update_size(&self) -> usize {
+        self.inner.optimal_update_size()
+    }
+}
+
+/// The squeeze phase of a SHAKE128 hasher, created by [`Shake128::into_xof`].
+///
+/// Output can be squeezed as bytes, as bits, or through the [`Iterator`] implementation,
+/// but no more input can be absorbed.
+#[derive(Clone, Debug)]
+pub struct Shake128Xof {
+    inner: Shake,
+}
+
+impl Shake128Xof {
+    /// Squeeze exactly `output.len()` bytes into `output`.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying squeeze fails.
+    ///
+    pub fn get_bytes(&mut self, output: &mut [u8]) -> Result<()> {
+        let num_bytes = output.len();
+        self.inner.get_bytes(output, num_bytes)
+    }
+
+    /// Squeeze `num_bits` bits onto the end of `output`.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying squeeze fails.
+    ///
+    pub fn get_bits(&mut self, output: &mut BitVec<u8, Lsb0>, num_bits: usize) -> Result<()> {
+        self.inner.get_bits(output, num_bits)
+    }
+}
+
+impl Iterator for Shake128Xof {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut byte = [0u8; 1];
+        self.get_bytes(&mut byte).ok().map(|()| byte[0])
+    }
 }
 
 impl Default for Shake128 {
@@ -71,6 +159,27 @@ impl XofHasher for Shake128 {
     }
 }
 
+// Not imported, so calls to `update`/`finalize` in this module stay unambiguous
+impl traits::DynXof for Shake128 {
+    fn update(&mut self, data: &[u8]) -> Result<()> {
+        XofHasher::update(self, data)
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        XofHasher::finalize(self)
+    }
+
+    fn squeeze_to_vec(&mut self, num_bytes: usize) -> Result<Vec<u8>> {
+        let mut output = vec![0u8; num_bytes];
+        self.get_bytes(&mut output, num_bytes)?;
+        Ok(output)
+    }
+
+    fn security_bits(&self) -> usize {
+        SHAKE_128_CAPACITY / 2
+    }
+}
+
 impl XofHasherBits for Shake128 {
     fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()> {
         self.inner.update_bits(data)
@@ -87,8 +196,9 @@ mod test {
     use bitvec::{bits, order::Lsb0, vec::BitVec};
 
     use crate::{
-        Shake128, XofHasher, XofHasherBits, b2h,
+        Shake128, XofHasher, XofHasherBits, b2h, nist_format,
         test::{Mode, create_test_vector},
+        utils::{nist_msg_5, nist_msg_30},
     };
 
     const NUM_BITS: usize = 4096;
@@ -405,7 +515,7 @@ B0 26 CE DD 57 59 5B 1A B6 FE 88 A7 84 BE 0C 06";
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE128_0_BITS, res);
         Ok(())
     }
@@ -416,7 +526,7 @@ B0 26 CE DD 57 59 5B 1A B6 FE 88 A7 84 BE 0C 06";
         hasher.finalize()?;
         let result = hasher.by_ref().take(NUM_BYTES).collect::<Vec<u8>>();
         assert_eq!(NUM_BYTES, result.len());
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE128_0_BITS, res);
         let next = hasher.next();
         assert_eq!(Some(0x4C), next);
@@ -431,7 +541,7 @@ B0 26 CE DD 57 59 5B 1A B6 FE 88 A7 84 BE 0C 06";
         hasher.update(b"Hello, world!")?;
         let result = hasher.by_ref().take(NUM_BYTES).collect::<Vec<u8>>();
         assert_eq!(NUM_BYTES, result.len());
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE128_HELLO_WORLD, res);
         let next = hasher.next();
         assert_eq!(Some(0xF0), next);
@@ -487,11 +597,11 @@ B0 26 CE DD 57 59 5B 1A B6 FE 88 A7 84 BE 0C 06";
     /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/SHAKE128_Msg5.pdf>
     fn test_shake128_5_bits() -> Result<()> {
         let mut hasher = Shake128::new();
-        hasher.update_bits(bits![u8, Lsb0; 1, 1, 0, 0, 1])?;
+        hasher.update_bits(&nist_msg_5())?;
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE128_5_BITS, res);
         Ok(())
     }
@@ -500,11 +610,11 @@ B0 26 CE DD 57 59 5B 1A B6 FE 88 A7 84 BE 0C 06";
     /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/SHAKE128_Msg30.pdf>
     fn test_shake128_30_bits() -> Result<()> {
         let mut hasher = Shake128::new();
-        hasher.update_bits(bits![u8, Lsb0; 1, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 1, 0, 1, 0, 1, 1, 0, 1, 1, 1, 1, 0, 1, 0, 0, 1, 1, 0])?;
+        hasher.update_bits(&nist_msg_30())?;
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE128_30_BITS, res);
         Ok(())
     }
@@ -520,7 +630,7 @@ B0 26 CE DD 57 59 5B 1A B6 FE 88 A7 84 BE 0C 06";
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE128_1600_BITS, res);
         Ok(())
     }
@@ -536,7 +646,7 @@ B0 26 CE DD 57 59 5B 1A B6 FE 88 A7 84 BE 0C 06";
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE128_1605_BITS, res);
         Ok(())
     }
@@ -552,7 +662,7 @@ B0 26 CE DD 57 59 5B 1A B6 FE 88 A7 84 BE 0C 06";
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE128_1630_BITS, res);
         Ok(())
     }
@@ -575,4 +685,60 @@ B0 26 CE DD 57 59 5B 1A B6 FE 88 A7 84 BE 0C 06";
         assert!(hasher.finalize().is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_shake128_into_xof() -> Result<()> {
+        let mut expected = Shake128::new();
+        expected.update(b"Hello, world!")?;
+        let expected = expected.take(64).collect::<Vec<u8>>();
+
+        let mut hasher = Shake128::new();
+        hasher.update(b"Hello, world!")?;
+        let mut xof = hasher.into_xof()?;
+        let mut output = [0u8; 16];
+        xof.get_bytes(&mut output)?;
+        let mut bits = BitVec::<u8, Lsb0>::new();
+        xof.get_bits(&mut bits, 128)?;
+        assert_eq!(expected[..16], output);
+        assert_eq!(expected[16..32], bits.into_vec());
+        assert_eq!(expected[32..], xof.take(32).collect::<Vec<u8>>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_shake128_into_xof_after_finalize() -> Result<()> {
+        let mut expected = Shake128::new();
+        let expected = expected.by_ref().take(8).collect::<Vec<u8>>();
+
+        let mut hasher = Shake128::new();
+        hasher.finalize()?;
+        let mut first = [0u8; 4];
+        hasher.get_bytes(&mut first, 4)?;
+        let rest = hasher.into_xof()?.take(4).collect::<Vec<u8>>();
+        assert_eq!(expected[..4], first);
+        assert_eq!(expected[4..], rest);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shake128_squeeze_with() -> Result<()> {
+        let mut expected = Shake128::new();
+        expected.update(b"Hello, world!")?;
+        expected.finalize()?;
+        let mut direct = [0u8; 400];
+        expected.get_bytes(&mut direct, 400)?;
+
+        let mut hasher = Shake128::new();
+        hasher.update(b"Hello, world!")?;
+        hasher.finalize()?;
+        let mut chunks = Vec::new();
+        hasher.squeeze_with(400, |chunk| {
+            chunks.push(chunk.to_vec());
+            Ok(())
+        })?;
+        let lens = chunks.iter().map(Vec::len).collect::<Vec<usize>>();
+        assert_eq!(vec![168, 168, 64], lens);
+        assert_eq!(direct.to_vec(), chunks.concat());
+        Ok(())
+    }
 }
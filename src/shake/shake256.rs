@@ -12,10 +12,13 @@ use bitvec::{order::Lsb0, slice::BitSlice, vec::BitVec};
 use crate::{
     XofHasher, XofHasherBits,
     constants::{SHAKE_256_CAPACITY, SHAKE_256_RATE},
-    shake::Shake,
-    sponge::Keccak1600Sponge,
+    shake::{Shake, shake_suffix},
+    traits,
 };
 
+// The size of the chaining value squeezed from each input to `Shake256::combine`
+const COMBINE_CHAINING_BYTES: usize = SHAKE_256_CAPACITY / 8;
+
 /// SHAKE256 XOF function (`SHAKE256(M, d) = KECCAK[512](M||1111, d)`)
 #[derive(Clone, Debug)]
 pub struct Shake256 {
@@ -29,10 +32,181 @@ impl Shake256 {
     pub fn new() -> Self {
         Self {
             finalized: false,
-            inner: Shake {
-                sponge: Keccak1600Sponge::new(SHAKE_256_RATE, SHAKE_256_CAPACITY),
-            },
+            inner: Shake::new(SHAKE_256_RATE, SHAKE_256_CAPACITY, shake_suffix()),
+        }
+    }
+
+    pub(crate) fn finalized(&self) -> bool {
+        self.inner.finalized()
+    }
+
+    /// Combine two independently absorbed SHAKE256 hashers into a new hasher.
+    ///
+    /// Clones of `a` and `b` are finalized and a 64 byte chaining value is squeezed from
+    /// each.  The result is a fresh, unfinalized SHAKE256 hasher that has absorbed the
+    /// chaining value of `a` followed by the chaining value of `b`, so the order of the
+    /// arguments matters.  Neither `a` nor `b` is modified.
+    ///
+    /// This is an application-level construction for merging transcripts, not a Keccak
+    /// primitive: the result is not the SHAKE256 hash of any concatenation of the inputs.
+    ///
+    /// # Errors
+    /// An error will be returned if either hasher has already been finalized.
+    ///
+    pub fn combine(a: &Self, b: &Self) -> Result<Self> {
+        let mut combined = Self::new();
+        for hasher in [a, b] {
+            let mut chaining_value = [0u8; COMBINE_CHAINING_BYTES];
+            let mut hasher = hasher.clone();
+            hasher.finalize()?;
+            hasher.get_bytes(&mut chaining_value, COMBINE_CHAINING_BYTES)?;
+            combined.update(&chaining_value)?;
+        }
+        Ok(combined)
+    }
+
+    /// Finalize the hasher and convert it into a [`Shake256Xof`] that can only squeeze.
+    ///
+    /// The returned type has no `update`, so absorbing after the absorb phase has ended
+    /// is a compile error rather than a runtime [`Finalized`](crate::Sha3Error::Finalized)
+    /// error.  If the hasher has already been finalized, the squeeze continues from where
+    /// it left off.
+    ///
+    /// ```compile_fail
+    /// # use anyhow::Result;
+    /// # use shashasha::{Shake256, XofHasher};
+    /// # pub fn main() -> Result<()> {
+    /// let mut xof = Shake256::new().into_xof()?;
+    /// xof.update(b"too late")?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// An error will be returned if the message cannot be padded and absorbed.
+    ///
+    pub fn into_xof(mut self) -> Result<Shake256Xof> {
+        if !self.inner.finalized() {
+            self.inner.finalize()?;
         }
+        Ok(Shake256Xof { inner: self.inner })
+    }
+
+    /// Squeeze `num_bytes` bytes, passing them to `f` in chunks of at most the rate
+    /// (136 bytes) rather than buffering the whole output.
+    ///
+    /// The chunks are delivered in order and their concatenation is exactly what
+    /// [`get_bytes`](XofHasher::get_bytes) would have produced; only the final chunk may
+    /// be shorter than the rate.  The hasher must be finalized first.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying squeeze fails, and any error returned
+    /// by `f` stops the squeeze and is passed through.
+    ///
+    pub fn squeeze_with<F>(&mut self, num_bytes: usize, f: F) -> Result<()>
+    where
+        F: FnMut(&[u8]) -> Result<()>,
+    {
+        self.inner.squeeze_with(num_bytes, f)
+    }
+
+    /// Serialize the current squeeze position as a token that
+    /// [`from_token`](Self::from_token) can resume from.
+    ///
+    /// The token is 208 bytes: the 25 Keccak-f\[1600\] state lanes followed by the number
+    /// of output bits squeezed from the state but not yet returned, each as a little-endian
+    /// `u64`.  Resuming from it continues the output exactly where this hasher is now, so a
+    /// long squeeze can be split across processes.  The token reveals as much about the
+    /// input as the hasher itself does, and it does not include the input.
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use shashasha::{Shake256, XofHasher};
+    /// # pub fn main() -> Result<()> {
+    /// let mut hasher = Shake256::new();
+    /// hasher.update(b"Hello, world!")?;
+    /// hasher.finalize()?;
+    /// let mut first = [0u8; 10];
+    /// hasher.get_bytes(&mut first, 10)?;
+    ///
+    /// let mut resumed = Shake256::from_token(&hasher.squeeze_token()?)?;
+    /// let (mut expected, mut actual) = ([0u8; 10], [0u8; 10]);
+    /// hasher.get_bytes(&mut expected, 10)?;
+    /// resumed.get_bytes(&mut actual, 10)?;
+    /// assert_eq!(expected, actual);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has not been finalized.
+    ///
+    pub fn squeeze_token(&self) -> Result<Vec<u8>> {
+        self.inner.squeeze_token()
+    }
+
+    /// Resume a finalized hasher from a token created by
+    /// [`squeeze_token`](Self::squeeze_token).
+    ///
+    /// # Errors
+    /// An error will be returned if the token is not 208 bytes, or if it records more
+    /// buffered output bits than the 1088 bit rate.
+    ///
+    pub fn from_token(token: &[u8]) -> Result<Self> {
+        Ok(Self {
+            finalized: true,
+            inner: Shake::from_squeeze_token(
+                SHAKE_256_RATE,
+                SHAKE_256_CAPACITY,
+                shake_suffix(),
+                token,
+            )?,
+        })
+    }
+
+    /// The SHAKE256 rate, 136 bytes.
+    #[must_use]
+    pub fn optimal_update_size(&self) -> usize {
+        self.inner.optimal_update_size()
+    }
+}
+
+/// The squeeze phase of a SHAKE256 hasher, created by [`Shake256::into_xof`].
+///
+/// Output can be squeezed as bytes, as bits, or through the [`Iterator`] implementation,
+/// but no more input can be absorbed.
+#[derive(Clone, Debug)]
+pub struct Shake256Xof {
+    inner: Shake,
+}
+
+impl Shake256Xof {
+    /// Squeeze exactly `output.len()` bytes into `output`.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying squeeze fails.
+    ///
+    pub fn get_bytes(&mut self, output: &mut [u8]) -> Result<()> {
+        let num_bytes = output.len();
+        self.inner.get_bytes(output, num_bytes)
+    }
+
+    /// Squeeze `num_bits` bits onto the end of `output`.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying squeeze fails.
+    ///
+    pub fn get_bits(&mut self, output: &mut BitVec<u8, Lsb0>, num_bits: usize) -> Result<()> {
+        self.inner.get_bits(output, num_bits)
+    }
+}
+
+impl Iterator for Shake256Xof {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut byte = [0u8; 1];
+        self.get_bytes(&mut byte).ok().map(|()| byte[0])
     }
 }
 
@@ -75,6 +249,27 @@ impl XofHasher for Shake256 {
     }
 }
 
+// Not imported, so calls to `update`/`finalize` in this module stay unambiguous
+impl traits::DynXof for Shake256 {
+    fn update(&mut self, data: &[u8]) -> Result<()> {
+        XofHasher::update(self, data)
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        XofHasher::finalize(self)
+    }
+
+    fn squeeze_to_vec(&mut self, num_bytes: usize) -> Result<Vec<u8>> {
+        let mut output = vec![0u8; num_bytes];
+        self.get_bytes(&mut output, num_bytes)?;
+        Ok(output)
+    }
+
+    fn security_bits(&self) -> usize {
+        SHAKE_256_CAPACITY / 2
+    }
+}
+
 impl XofHasherBits for Shake256 {
     fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()> {
         self.inner.update_bits(data)
@@ -91,8 +286,9 @@ mod test {
     use bitvec::{bits, order::Lsb0, vec::BitVec};
 
     use crate::{
-        Shake256, XofHasher, XofHasherBits, b2h,
+        Shake256, XofHasher, XofHasherBits, b2h, first_divergence, interleave_xof, nist_format,
         test::{Mode, create_test_vector},
+        utils::{nist_msg_5, nist_msg_30},
     };
 
     const NUM_BITS: usize = 4096;
@@ -329,7 +525,7 @@ E0 E7 55 37 35 88 02 EF 08 53 B7 47 0B 0F 19 AC";
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE256_0_BITS, res);
         Ok(())
     }
@@ -340,7 +536,7 @@ E0 E7 55 37 35 88 02 EF 08 53 B7 47 0B 0F 19 AC";
         hasher.finalize()?;
         let result = hasher.by_ref().take(NUM_BYTES).collect::<Vec<u8>>();
         assert_eq!(NUM_BYTES, result.len());
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE256_0_BITS, res);
         let next = hasher.next();
         assert_eq!(Some(0x5B), next);
@@ -355,7 +551,7 @@ E0 E7 55 37 35 88 02 EF 08 53 B7 47 0B 0F 19 AC";
         hasher.update(b"Hello, world!")?;
         let result = hasher.by_ref().take(NUM_BYTES).collect::<Vec<u8>>();
         assert_eq!(NUM_BYTES, result.len());
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE256_HELLO_WORLD, res);
         let next = hasher.next();
         assert_eq!(Some(0xA4), next);
@@ -368,11 +564,11 @@ E0 E7 55 37 35 88 02 EF 08 53 B7 47 0B 0F 19 AC";
     /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/SHAKE256_Msg5.pdf>
     fn test_shake256_5_bits() -> Result<()> {
         let mut hasher = Shake256::new();
-        hasher.update_bits(bits![u8, Lsb0; 1, 1, 0, 0, 1])?;
+        hasher.update_bits(&nist_msg_5())?;
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE256_5_BITS, res);
         Ok(())
     }
@@ -381,11 +577,11 @@ E0 E7 55 37 35 88 02 EF 08 53 B7 47 0B 0F 19 AC";
     /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/SHAKE256_Msg30.pdf>
     fn test_shake256_30_bits() -> Result<()> {
         let mut hasher = Shake256::new();
-        hasher.update_bits(bits![u8, Lsb0; 1, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 1, 0, 1, 0, 1, 1, 0, 1, 1, 1, 1, 0, 1, 0, 0, 1, 1, 0])?;
+        hasher.update_bits(&nist_msg_30())?;
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE256_30_BITS, res);
         Ok(())
     }
@@ -401,7 +597,7 @@ E0 E7 55 37 35 88 02 EF 08 53 B7 47 0B 0F 19 AC";
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE256_1600_BITS, res);
         Ok(())
     }
@@ -417,7 +613,7 @@ E0 E7 55 37 35 88 02 EF 08 53 B7 47 0B 0F 19 AC";
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE256_1605_BITS, res);
         Ok(())
     }
@@ -433,7 +629,7 @@ E0 E7 55 37 35 88 02 EF 08 53 B7 47 0B 0F 19 AC";
         let mut result = [0u8; NUM_BYTES];
         hasher.finalize()?;
         hasher.get_bytes(&mut result, NUM_BYTES)?;
-        let res = b2h(&BitVec::from_slice(&result), true, true)?;
+        let res = nist_format(&result);
         assert_eq!(SHAKE256_1630_BITS, res);
         Ok(())
     }
@@ -480,4 +676,222 @@ E0 E7 55 37 35 88 02 EF 08 53 B7 47 0B 0F 19 AC";
         assert!(hasher.finalize().is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_shake256_combine() -> Result<()> {
+        let mut a = Shake256::new();
+        a.update(b"transcript a")?;
+        let mut b = Shake256::new();
+        b.update(b"transcript b")?;
+
+        let mut ab = [0u8; 32];
+        let mut combined = Shake256::combine(&a, &b)?;
+        combined.finalize()?;
+        combined.get_bytes(&mut ab, 32)?;
+
+        let mut ab_again = [0u8; 32];
+        let mut combined = Shake256::combine(&a, &b)?;
+        combined.finalize()?;
+        combined.get_bytes(&mut ab_again, 32)?;
+
+        let mut ba = [0u8; 32];
+        let mut combined = Shake256::combine(&b, &a)?;
+        combined.finalize()?;
+        combined.get_bytes(&mut ba, 32)?;
+
+        assert_eq!(ab, ab_again);
+        assert_ne!(ab, ba);
+
+        // the inputs are left untouched
+        a.finalize()?;
+        assert!(Shake256::combine(&a, &b).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_shake256_into_xof() -> Result<()> {
+        let mut expected = Shake256::new();
+        expected.update(b"Hello, world!")?;
+        let expected = expected.take(64).collect::<Vec<u8>>();
+
+        let mut hasher = Shake256::new();
+        hasher.update(b"Hello, world!")?;
+        let mut xof = hasher.into_xof()?;
+        let mut output = [0u8; 16];
+        xof.get_bytes(&mut output)?;
+        let mut bits = BitVec::<u8, Lsb0>::new();
+        xof.get_bits(&mut bits, 128)?;
+        assert_eq!(expected[..16], output);
+        assert_eq!(expected[16..32], bits.into_vec());
+        assert_eq!(expected[32..], xof.take(32).collect::<Vec<u8>>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_shake256_into_xof_after_finalize() -> Result<()> {
+        let mut expected = Shake256::new();
+        let expected = expected.by_ref().take(8).collect::<Vec<u8>>();
+
+        let mut hasher = Shake256::new();
+        hasher.finalize()?;
+        let mut first = [0u8; 4];
+        hasher.get_bytes(&mut first, 4)?;
+        let rest = hasher.into_xof()?.take(4).collect::<Vec<u8>>();
+        assert_eq!(expected[..4], first);
+        assert_eq!(expected[4..], rest);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shake256_squeeze_with() -> Result<()> {
+        let mut expected = Shake256::new();
+        expected.update(b"Hello, world!")?;
+        expected.finalize()?;
+        let mut direct = [0u8; 400];
+        expected.get_bytes(&mut direct, 400)?;
+
+        let mut hasher = Shake256::new();
+        hasher.update(b"Hello, world!")?;
+        hasher.finalize()?;
+        let mut chunks = Vec::new();
+        hasher.squeeze_with(400, |chunk| {
+            chunks.push(chunk.to_vec());
+            Ok(())
+        })?;
+        let lens = chunks.iter().map(Vec::len).collect::<Vec<usize>>();
+        assert_eq!(vec![136, 136, 128], lens);
+        assert_eq!(direct.to_vec(), chunks.concat());
+        Ok(())
+    }
+
+    fn seeded(seed: &[u8]) -> Result<Shake256> {
+        let mut hasher = Shake256::new();
+        hasher.update(seed)?;
+        hasher.finalize()?;
+        Ok(hasher)
+    }
+
+    #[test]
+    fn test_interleave_xof() -> Result<()> {
+        let mut a = seeded(b"stream a")?;
+        let mut b = seeded(b"stream b")?;
+        let mut out = [0u8; 8];
+        interleave_xof(&mut a, &mut b, &mut out)?;
+
+        let mut a_bytes = [0u8; 4];
+        let mut b_bytes = [0u8; 4];
+        seeded(b"stream a")?.get_bytes(&mut a_bytes, 4)?;
+        seeded(b"stream b")?.get_bytes(&mut b_bytes, 4)?;
+        let expected = a_bytes
+            .iter()
+            .zip(&b_bytes)
+            .flat_map(|(x, y)| [*x, *y])
+            .collect::<Vec<u8>>();
+        assert_eq!(expected, out);
+
+        // An odd length takes the extra byte from the first stream
+        let mut a = seeded(b"stream a")?;
+        let mut b = seeded(b"stream b")?;
+        let mut odd = [0u8; 7];
+        interleave_xof(&mut a, &mut b, &mut odd)?;
+        assert_eq!(expected[..7], odd);
+        let mut next = [0u8; 1];
+        a.get_bytes(&mut next, 1)?;
+        let mut later = [0u8; 5];
+        seeded(b"stream a")?.get_bytes(&mut later, 5)?;
+        assert_eq!(later[4], next[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_squeeze_token_resumes_output() -> Result<()> {
+        let mut whole = Shake256::new();
+        whole.update(b"squeeze token")?;
+        whole.finalize()?;
+        let mut expected = [0u8; 402];
+        whole.get_bytes(&mut expected, 402)?;
+
+        let mut hasher = Shake256::new();
+        hasher.update(b"squeeze token")?;
+        assert!(hasher.squeeze_token().is_err());
+        hasher.finalize()?;
+        let mut first = [0u8; 32];
+        hasher.get_bytes(&mut first, 32)?;
+        let token = hasher.squeeze_token()?;
+        assert_eq!(208, token.len());
+
+        let mut resumed = Shake256::from_token(&token)?;
+        let mut second = [0u8; 32];
+        resumed.get_bytes(&mut second, 32)?;
+        assert_eq!(expected[..32], first);
+        assert_eq!(expected[32..64], second);
+
+        // Tokens taken at a block boundary and mid-byte resume just as well
+        let mut resumed = Shake256::from_token(&token)?;
+        let mut skip = [0u8; 104];
+        resumed.get_bytes(&mut skip, 104)?;
+        let mut resumed = Shake256::from_token(&resumed.squeeze_token()?)?;
+        let mut rest = [0u8; 264];
+        resumed.get_bytes(&mut rest, 264)?;
+        assert_eq!(expected[136..400], rest);
+
+        let mut bits = BitVec::<u8, Lsb0>::new();
+        resumed.get_bits(&mut bits, 3)?;
+        let mut resumed = Shake256::from_token(&resumed.squeeze_token()?)?;
+        resumed.get_bits(&mut bits, 13)?;
+        assert_eq!(expected[400..], *bits.as_raw_slice());
+
+        assert!(Shake256::from_token(&token[..207]).is_err());
+        let mut too_many_bits = token.clone();
+        too_many_bits[200..].copy_from_slice(&1089u64.to_le_bytes());
+        assert!(Shake256::from_token(&too_many_bits).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_first_divergence() -> Result<()> {
+        let finalized = |data: &[u8]| -> Result<Shake256> {
+            let mut hasher = Shake256::new();
+            hasher.update(data)?;
+            hasher.finalize()?;
+            Ok(hasher)
+        };
+        assert_eq!(
+            None,
+            first_divergence(&mut finalized(b"same")?, &mut finalized(b"same")?, 1000)?
+        );
+        assert_eq!(
+            Some(0),
+            first_divergence(&mut finalized(b"one")?, &mut finalized(b"two")?, 1000)?
+        );
+        assert_eq!(
+            None,
+            first_divergence(&mut finalized(b"one")?, &mut finalized(b"two")?, 0)?
+        );
+
+        // The first output block is the leading state bytes, so flipping a bit of state
+        // byte `i` in a squeeze token gives a stream that first differs at byte `i`
+        let token = finalized(b"prefix")?.squeeze_token()?;
+        for i in [10, 135] {
+            let mut flipped = token.clone();
+            flipped[i] ^= 0x01;
+            let mut a = Shake256::from_token(&token)?;
+            let mut b = Shake256::from_token(&flipped)?;
+            assert_eq!(Some(i), first_divergence(&mut a, &mut b, 1000)?);
+        }
+        let mut flipped = token.clone();
+        flipped[10] ^= 0x01;
+        let mut a = Shake256::from_token(&token)?;
+        let mut b = Shake256::from_token(&flipped)?;
+        assert_eq!(None, first_divergence(&mut a, &mut b, 10)?);
+
+        // Unfinalized streams are an error, not identical
+        let mut a = Shake256::new();
+        let mut b = Shake256::new();
+        a.update(b"one")?;
+        b.update(b"two")?;
+        assert!(first_divergence(&mut a, &mut b, 1000).is_err());
+        assert!(first_divergence(&mut finalized(b"one")?, &mut b, 1000).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,296 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use anyhow::Result;
+use bitvec::{bits, order::Lsb0, slice::BitSlice, vec::BitVec};
+
+use crate::{
+    XofHasher, XofHasherBits,
+    constants::{SHAKE_128_CAPACITY, SHAKE_128_RATE, SHAKE_256_CAPACITY, SHAKE_256_RATE},
+    shake::{Shake, shake_suffix},
+    sp800_185::encoding::{bytepad, encode_string},
+};
+
+/// The cSHAKE domain separation bits (`00`)
+fn cshake_suffix() -> &'static BitSlice<u8, Lsb0> {
+    bits![static u8, Lsb0; 0, 0]
+}
+
+/// Build the inner SHAKE state for cSHAKE, absorbing the encoded function name and
+/// customization string.  If both are empty, cSHAKE is defined to be plain SHAKE.
+fn cshake(
+    rate: usize,
+    capacity: usize,
+    function_name: &[u8],
+    customization: &[u8],
+) -> Result<Shake> {
+    if function_name.is_empty() && customization.is_empty() {
+        Ok(Shake::new(rate, capacity, shake_suffix()))
+    } else {
+        let mut shake = Shake::new(rate, capacity, cshake_suffix());
+        let mut prefix = encode_string(function_name);
+        prefix.extend(encode_string(customization));
+        shake.update(&bytepad(&prefix, rate / 8))?;
+        Ok(shake)
+    }
+}
+
+/// cSHAKE128 XOF function (`cSHAKE128(X, L, N, S) = KECCAK[256](bytepad(encode_string(N) || encode_string(S), 168) || X || 00, L)`)
+#[derive(Clone, Debug)]
+pub struct CShake128 {
+    inner: Shake,
+}
+
+impl CShake128 {
+    /// Create a new cSHAKE128 XOF hasher instance with the given function name (`N`)
+    /// and customization string (`S`).
+    ///
+    /// If both `function_name` and `customization` are empty this is equivalent to SHAKE128.
+    ///
+    /// # Errors
+    /// An error will be returned if the encoded prefix cannot be absorbed.
+    ///
+    pub fn new(function_name: &[u8], customization: &[u8]) -> Result<Self> {
+        Ok(Self {
+            inner: cshake(
+                SHAKE_128_RATE,
+                SHAKE_128_CAPACITY,
+                function_name,
+                customization,
+            )?,
+        })
+    }
+
+    /// The cSHAKE128 rate, 168 bytes.
+    #[must_use]
+    pub fn optimal_update_size(&self) -> usize {
+        self.inner.optimal_update_size()
+    }
+}
+
+impl Iterator for CShake128 {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.inner.finalized() && self.finalize().is_err() {
+            None
+        } else {
+            let mut byte = [0u8; 1];
+            if self.get_bytes(&mut byte, 1).is_ok() {
+                Some(byte[0])
+            } else {
+                None
+            }
+        }
+    }
+}
+
+impl XofHasher for CShake128 {
+    fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.inner.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        self.inner.finalize()
+    }
+
+    fn get_bytes(&mut self, output: &mut [u8], num_bytes: usize) -> Result<()> {
+        self.inner.get_bytes(output, num_bytes)
+    }
+}
+
+impl XofHasherBits for CShake128 {
+    fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()> {
+        self.inner.update_bits(data)
+    }
+
+    fn get_bits(&mut self, output: &mut BitVec<u8, Lsb0>, num_bits: usize) -> Result<()> {
+        self.inner.get_bits(output, num_bits)
+    }
+}
+
+/// cSHAKE256 XOF function (`cSHAKE256(X, L, N, S) = KECCAK[512](bytepad(encode_string(N) || encode_string(S), 136) || X || 00, L)`)
+#[derive(Clone, Debug)]
+pub struct CShake256 {
+    inner: Shake,
+}
+
+impl CShake256 {
+    /// Create a new cSHAKE256 XOF hasher instance with the given function name (`N`)
+    /// and customization string (`S`).
+    ///
+    /// If both `function_name` and `customization` are empty this is equivalent to SHAKE256.
+    ///
+    /// # Errors
+    /// An error will be returned if the encoded prefix cannot be absorbed.
+    ///
+    pub fn new(function_name: &[u8], customization: &[u8]) -> Result<Self> {
+        Ok(Self {
+            inner: cshake(
+                SHAKE_256_RATE,
+                SHAKE_256_CAPACITY,
+                function_name,
+                customization,
+            )?,
+        })
+    }
+
+    /// The cSHAKE256 rate, 136 bytes.
+    #[must_use]
+    pub fn optimal_update_size(&self) -> usize {
+        self.inner.optimal_update_size()
+    }
+}
+
+impl Iterator for CShake256 {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.inner.finalized() && self.finalize().is_err() {
+            None
+        } else {
+            let mut byte = [0u8; 1];
+            if self.get_bytes(&mut byte, 1).is_ok() {
+                Some(byte[0])
+            } else {
+                None
+            }
+        }
+    }
+}
+
+impl XofHasher for CShake256 {
+    fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.inner.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<()> {
+        self.inner.finalize()
+    }
+
+    fn get_bytes(&mut self, output: &mut [u8], num_bytes: usize) -> Result<()> {
+        self.inner.get_bytes(output, num_bytes)
+    }
+}
+
+impl XofHasherBits for CShake256 {
+    fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()> {
+        self.inner.update_bits(data)
+    }
+
+    fn get_bits(&mut self, output: &mut BitVec<u8, Lsb0>, num_bits: usize) -> Result<()> {
+        self.inner.get_bits(output, num_bits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use crate::{CShake128, CShake256, Shake128, Shake256, XofHasher, nist_format};
+
+    const EMAIL_SIGNATURE: &[u8] = b"Email Signature";
+    const CSHAKE128_SAMPLE_1: &str = "C1 C3 69 25 B6 40 9A 04 F1 B5 04 FC BC A9 D8 2B \
+40 17 27 7C B5 ED 2B 20 65 FC 1D 38 14 D5 AA F5";
+    const CSHAKE128_SAMPLE_2: &str = "C5 22 1D 50 E4 F8 22 D9 6A 2E 88 81 A9 61 42 0F \
+29 4B 7B 24 FE 3D 20 94 BA ED 2C 65 24 CC 16 6B";
+    const CSHAKE256_SAMPLE_3: &str = "D0 08 82 8E 2B 80 AC 9D 22 18 FF EE 1D 07 0C 48 \
+B8 E4 C8 7B FF 32 C9 69 9D 5B 68 96 EE E0 ED D1 \
+64 02 0E 2B E0 56 08 58 D9 C0 0C 03 7E 34 A9 69 \
+37 C5 61 A7 4C 41 2B B4 C7 46 46 95 27 28 1C 8C";
+    const CSHAKE256_SAMPLE_4: &str = "07 DC 27 B1 1E 51 FB AC 75 BC 7B 3C 1D 98 3E 8B \
+4B 85 FB 1D EF AF 21 89 12 AC 86 43 02 73 09 17 \
+27 F4 2B 17 ED 1D F6 3E 8E C1 18 F0 4B 23 63 3C \
+1D FB 15 74 C8 FB 55 CB 45 DA 8E 25 AF B0 92 BB";
+
+    fn sample_data(len: u8) -> Vec<u8> {
+        (0..len).collect()
+    }
+
+    #[test]
+    /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/cSHAKE_samples.pdf>
+    fn test_cshake128_sample_1() -> Result<()> {
+        let mut hasher = CShake128::new(b"", EMAIL_SIGNATURE)?;
+        let mut result = [0u8; 32];
+        hasher.update(&sample_data(4))?;
+        hasher.finalize()?;
+        hasher.get_bytes(&mut result, 32)?;
+        let res = nist_format(&result);
+        assert_eq!(CSHAKE128_SAMPLE_1, res);
+        Ok(())
+    }
+
+    #[test]
+    /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/cSHAKE_samples.pdf>
+    fn test_cshake128_sample_2() -> Result<()> {
+        let mut hasher = CShake128::new(b"", EMAIL_SIGNATURE)?;
+        let mut result = [0u8; 32];
+        hasher.update(&sample_data(200))?;
+        hasher.finalize()?;
+        hasher.get_bytes(&mut result, 32)?;
+        let res = nist_format(&result);
+        assert_eq!(CSHAKE128_SAMPLE_2, res);
+        Ok(())
+    }
+
+    #[test]
+    /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/cSHAKE_samples.pdf>
+    fn test_cshake256_sample_3() -> Result<()> {
+        let mut hasher = CShake256::new(b"", EMAIL_SIGNATURE)?;
+        let mut result = [0u8; 64];
+        hasher.update(&sample_data(4))?;
+        hasher.finalize()?;
+        hasher.get_bytes(&mut result, 64)?;
+        let res = nist_format(&result);
+        assert_eq!(CSHAKE256_SAMPLE_3, res);
+        Ok(())
+    }
+
+    #[test]
+    /// <https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/cSHAKE_samples.pdf>
+    fn test_cshake256_sample_4() -> Result<()> {
+        let mut hasher = CShake256::new(b"", EMAIL_SIGNATURE)?;
+        hasher.update(&sample_data(200))?;
+        let result = hasher.by_ref().take(64).collect::<Vec<u8>>();
+        let res = nist_format(&result);
+        assert_eq!(CSHAKE256_SAMPLE_4, res);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cshake_empty_is_shake() -> Result<()> {
+        let mut cshake = CShake128::new(b"", b"")?;
+        cshake.update(b"Hello, world!")?;
+        let mut shake = Shake128::new();
+        shake.update(b"Hello, world!")?;
+        assert_eq!(
+            cshake.take(64).collect::<Vec<u8>>(),
+            shake.take(64).collect::<Vec<u8>>()
+        );
+
+        let mut cshake = CShake256::new(b"", b"")?;
+        cshake.update(b"Hello, world!")?;
+        let mut shake = Shake256::new();
+        shake.update(b"Hello, world!")?;
+        assert_eq!(
+            cshake.take(64).collect::<Vec<u8>>(),
+            shake.take(64).collect::<Vec<u8>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cshake_update_after_finalize_error() -> Result<()> {
+        let mut hasher = CShake256::new(b"", EMAIL_SIGNATURE)?;
+        hasher.update(b"Yoda!")?;
+        hasher.finalize()?;
+        assert!(hasher.update(b"Hello, world!").is_err());
+        assert!(hasher.finalize().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! The string encoding functions defined in section 2.3 of SP 800-185
+
+/// `left_encode(x)` encodes the integer `x` as a byte string that can be unambiguously
+/// parsed from the beginning of the string (the byte count is prepended).
+pub(crate) fn left_encode(x: usize) -> Vec<u8> {
+    let bytes = x.to_be_bytes();
+    let skip = bytes
+        .iter()
+        .take_while(|b| **b == 0)
+        .count()
+        .min(bytes.len() - 1);
+    let mut encoded = Vec::with_capacity(bytes.len() - skip + 1);
+    encoded.push(u8::try_from(bytes.len() - skip).unwrap_or(u8::MAX));
+    encoded.extend_from_slice(&bytes[skip..]);
+    encoded
+}
+
+/// `encode_string(S)` encodes the bit string `S` as `left_encode(len(S)) || S`, where
+/// `len(S)` is the length of `S` in bits.
+pub(crate) fn encode_string(s: &[u8]) -> Vec<u8> {
+    let mut encoded = left_encode(s.len() * 8);
+    encoded.extend_from_slice(s);
+    encoded
+}
+
+/// `bytepad(X, w)` prepends `left_encode(w)` to `X` and then pads the result with zeros
+/// until it is a multiple of `w` bytes long.
+pub(crate) fn bytepad(x: &[u8], w: usize) -> Vec<u8> {
+    let mut padded = left_encode(w);
+    padded.extend_from_slice(x);
+    while w > 0 && padded.len() % w != 0 {
+        padded.push(0);
+    }
+    padded
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bytepad, encode_string, left_encode};
+
+    #[test]
+    fn test_left_encode() {
+        assert_eq!(vec![0x01, 0x00], left_encode(0));
+        assert_eq!(vec![0x01, 0xA8], left_encode(168));
+        assert_eq!(vec![0x02, 0x01, 0x00], left_encode(256));
+        assert_eq!(vec![0x02, 0x01, 0x2C], left_encode(300));
+    }
+
+    #[test]
+    fn test_encode_string() {
+        assert_eq!(vec![0x01, 0x00], encode_string(b""));
+        assert_eq!(vec![0x01, 0x18, b'a', b'b', b'c'], encode_string(b"abc"));
+    }
+
+    #[test]
+    fn test_bytepad() {
+        let padded = bytepad(&encode_string(b"abc"), 8);
+        assert_eq!(vec![0x01, 0x08, 0x01, 0x18, b'a', b'b', b'c', 0x00], padded);
+        let padded = bytepad(&[0u8; 6], 8);
+        assert_eq!(8, padded.len());
+        let padded = bytepad(&[0u8; 7], 8);
+        assert_eq!(16, padded.len());
+    }
+}
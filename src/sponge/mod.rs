@@ -7,20 +7,54 @@
 // modified, or distributed except according to those terms.
 
 use anyhow::Result;
-use bitvec::{field::BitField, order::Lsb0, slice::BitSlice, vec::BitVec, view::BitView};
+use bitvec::{field::BitField, order::Lsb0, slice::BitSlice, vec::BitVec};
 
-use crate::{Sha3Error, constants::LANE_COUNT, f_1600, traits::Sponge};
+use crate::{
+    Midstate, Sha3Error,
+    constants::{KECCAK_F_1600_ROUNDS, LANE_COUNT, SHA3_WIDTH},
+    p_1600,
+    traits::Sponge,
+};
 
+/// A Keccak-f\[1600\] sponge with a configurable rate and capacity (both in bits).
+#[cfg_attr(not(feature = "low-level"), allow(unreachable_pub))]
 #[derive(Clone, Debug)]
-pub(crate) struct Keccak1600Sponge {
+pub struct Keccak1600Sponge {
     // Internal state representation
     state: [u64; LANE_COUNT],
     // Message Data
     message: BitVec<u8, Lsb0>,
     rate: usize,
+    // Only the rate lanes are ever xored into, so the capacity is bookkeeping (and Debug output)
+    #[allow(dead_code)]
     capacity: usize,
     output: BitVec<u8, Lsb0>,
     finalized: bool,
+    // Number of message bits given to update/update_bits (excludes domain separation bits)
+    bits_absorbed: usize,
+    // Number of Keccak-p rounds per permutation (24 for Keccak-f[1600])
+    rounds: usize,
+    #[cfg(feature = "low-level")]
+    endian: Endian,
+    // The last block xored into the state, for debugging padding
+    #[cfg(feature = "low-level")]
+    last_block: Option<[u8; SHA3_WIDTH / 8]>,
+}
+
+/// The byte order used to pack message bytes into, and output bytes out of, the 64-bit
+/// lanes of the Keccak-f\[1600\] state.
+///
+/// FIPS 202 specifies little-endian packing, which every standard hasher in this crate
+/// uses.  Big-endian packing is only useful for reproducing traces from tools that
+/// document the state with big-endian lanes; its output is not SHA-3.
+#[cfg(feature = "low-level")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Endian {
+    /// Little-endian lanes (FIPS 202)
+    #[default]
+    Little,
+    /// Big-endian lanes
+    Big,
 }
 
 impl Default for Keccak1600Sponge {
@@ -29,10 +63,14 @@ impl Default for Keccak1600Sponge {
     }
 }
 
+#[cfg_attr(not(feature = "low-level"), allow(unreachable_pub))]
 impl Keccak1600Sponge {
-    /// Create a new Keccak-f[1600] sponge.
+    /// Create a new Keccak-f\[1600\] sponge.
+    ///
+    /// Both `rate` and `capacity` are given in **bits**, i.e. `(1088, 512)` for SHA3-256.
+    /// See [`new_bytes`](Self::new_bytes) to give them in bytes instead.
     #[must_use]
-    pub(crate) fn new(rate: usize, capacity: usize) -> Self {
+    pub fn new(rate: usize, capacity: usize) -> Self {
         Self {
             state: [0u64; LANE_COUNT],
             message: BitVec::new(),
@@ -40,6 +78,56 @@ impl Keccak1600Sponge {
             rate,
             capacity,
             finalized: false,
+            bits_absorbed: 0,
+            rounds: KECCAK_F_1600_ROUNDS,
+            #[cfg(feature = "low-level")]
+            endian: Endian::Little,
+            #[cfg(feature = "low-level")]
+            last_block: None,
+        }
+    }
+
+    /// Create a new Keccak-f\[1600\] sponge (`rate` and `capacity` in **bits**) that packs
+    /// lanes with the given byte order.
+    ///
+    /// This is a debugging aid for comparing against big-endian reference traces; only
+    /// [`Endian::Little`] produces standard FIPS 202 results.
+    #[cfg(feature = "low-level")]
+    #[must_use]
+    pub fn new_with_endian(rate: usize, capacity: usize, endian: Endian) -> Self {
+        Self {
+            endian,
+            ..Self::new(rate, capacity)
+        }
+    }
+
+    /// Create a new Keccak-f\[1600\] sponge (`rate` and `capacity` in **bits**) whose state
+    /// starts from `iv` instead of all zeros.
+    ///
+    /// This is for research into non-standard constructions; every FIPS 202 function
+    /// starts from the all-zero state, which is what [`new`](Self::new) uses.
+    #[cfg(feature = "low-level")]
+    #[must_use]
+    pub fn new_with_iv(rate: usize, capacity: usize, iv: [u64; LANE_COUNT]) -> Self {
+        Self {
+            state: iv,
+            ..Self::new(rate, capacity)
+        }
+    }
+
+    /// Create a new Keccak-f\[1600\] sponge with the rate and capacity given in **bytes**,
+    /// i.e. `(136, 64)` for SHA3-256.
+    ///
+    /// # Errors
+    /// An error will be returned if `rate_bytes + capacity_bytes` is not 200, the size of
+    /// the Keccak-f\[1600\] state in bytes.
+    ///
+    #[cfg(feature = "low-level")]
+    pub fn new_bytes(rate_bytes: usize, capacity_bytes: usize) -> Result<Self> {
+        if rate_bytes.checked_add(capacity_bytes) == Some(SHA3_WIDTH / 8) {
+            Ok(Self::new(rate_bytes * 8, capacity_bytes * 8))
+        } else {
+            Err(Sha3Error::InvalidSpongeParameters(rate_bytes, capacity_bytes).into())
         }
     }
 
@@ -47,72 +135,345 @@ impl Keccak1600Sponge {
         self.finalized
     }
 
-    fn xor_block(&mut self, bits: &BitVec<u8, Lsb0>) -> Result<()> {
-        let mut chunks = bits.chunks_exact(64);
+    /// Snapshot the state and buffered bits of an unfinalized sponge.
+    pub(crate) fn export_midstate(&self, suffix: &'static BitSlice<u8, Lsb0>) -> Result<Midstate> {
+        if self.finalized {
+            Err(Sha3Error::Finalized.into())
+        } else {
+            Ok(Midstate {
+                state: self.state,
+                remainder: self.message.clone(),
+                rate: self.rate,
+                bits_absorbed: self.bits_absorbed,
+                suffix,
+            })
+        }
+    }
 
-        for (s, chunk) in self.state.iter_mut().zip(&mut chunks) {
-            let mut value: u64 = 0;
-            for (j, bit) in chunk.iter().enumerate() {
-                value += u64::from(*bit) * 2u64.pow(j.try_into()?);
-            }
-            *s ^= value;
+    /// Create a new sponge (`rate` and `capacity` in bits) that reuses the allocations of
+    /// the given message and output buffers.  Both buffers are cleared.
+    pub(crate) fn with_buffers(
+        rate: usize,
+        capacity: usize,
+        mut message: BitVec<u8, Lsb0>,
+        mut output: BitVec<u8, Lsb0>,
+    ) -> Self {
+        message.clear();
+        output.clear();
+        Self {
+            message,
+            output,
+            ..Self::new(rate, capacity)
         }
-        Ok(())
     }
 
-    fn keccak(&mut self) -> Result<()> {
-        f_1600(&mut self.state)?;
-        Ok(())
+    /// Take the message and output buffers out of the sponge so their allocations can be
+    /// reused by [`with_buffers`](Self::with_buffers).
+    pub(crate) fn take_buffers(&mut self) -> (BitVec<u8, Lsb0>, BitVec<u8, Lsb0>) {
+        (
+            std::mem::take(&mut self.message),
+            std::mem::take(&mut self.output),
+        )
     }
 
-    fn fill_output(&mut self) {
-        self.output.clear();
-        let mut byte_vec = Vec::new();
-        for s in &self.state {
-            byte_vec.extend(s.to_le_bytes());
+    /// Use `rounds` rounds of Keccak-p\[1600\] per permutation instead of the 24 rounds of
+    /// Keccak-f\[1600\].
+    pub(crate) fn with_rounds(self, rounds: usize) -> Self {
+        Self { rounds, ..self }
+    }
+
+    /// Resume a sponge from a midstate exported by a sponge with the same rate.
+    pub(crate) fn from_midstate(midstate: &Midstate, capacity: usize) -> Self {
+        Self {
+            state: midstate.state,
+            message: midstate.remainder.clone(),
+            output: BitVec::new(),
+            rate: midstate.rate,
+            capacity,
+            finalized: false,
+            bits_absorbed: midstate.bits_absorbed,
+            rounds: KECCAK_F_1600_ROUNDS,
+            #[cfg(feature = "low-level")]
+            endian: Endian::Little,
+            #[cfg(feature = "low-level")]
+            last_block: None,
         }
+    }
 
-        let mut bits_vec = BitVec::<u8, Lsb0>::new();
+    /// The state and the number of buffered output bits of a finalized sponge, enough to
+    /// resume the squeeze with [`from_squeeze_position`](Self::from_squeeze_position).
+    pub(crate) fn squeeze_position(&self) -> Result<([u64; LANE_COUNT], usize)> {
+        if self.finalized {
+            Ok((self.state, self.output.len()))
+        } else {
+            Err(Sha3Error::NotFinalized.into())
+        }
+    }
 
-        for byte in byte_vec {
-            let slice = byte.view_bits::<Lsb0>();
-            bits_vec.extend_from_bitslice(slice);
+    /// Resume a finalized sponge mid-squeeze.  The output buffer is refilled from `state`
+    /// and its first `rate - buffered` bits are dropped, as they were already returned.
+    pub(crate) fn from_squeeze_position(
+        rate: usize,
+        capacity: usize,
+        state: [u64; LANE_COUNT],
+        buffered: usize,
+    ) -> Self {
+        let mut sponge = Self {
+            state,
+            finalized: true,
+            ..Self::new(rate, capacity)
+        };
+        if buffered > 0 {
+            sponge.fill_output();
+            sponge.output.reverse();
+            sponge.output.truncate(buffered);
         }
-        self.output.extend_from_bitslice(&bits_vec[..self.rate]);
+        sponge
     }
 
-    fn squeeze(&mut self, output: &mut [u8], num_bits: usize) -> Result<()> {
-        let mut bit_vec = BitVec::<u8, Lsb0>::new();
-        self.squeeze_b(&mut bit_vec, num_bits)?;
+    /// The rate of the sponge in bytes, which the hashers report as their
+    /// `optimal_update_size`.
+    ///
+    /// Each block of this many bytes triggers exactly one Keccak-f\[1600\] permutation
+    /// when absorbed, so batching `update` calls into multiples of this size avoids
+    /// buffering partial blocks between calls.
+    pub(crate) fn rate_bytes(&self) -> usize {
+        self.rate / 8
+    }
 
-        for (idx, eight_bits) in bit_vec.chunks_exact(8).enumerate() {
-            let value: u8 = eight_bits.load_le::<u8>();
-            output[idx] = value;
+    /// The number of message bits given to `update` and `update_bits` so far.
+    pub(crate) fn bits_absorbed(&self) -> usize {
+        self.bits_absorbed
+    }
+
+    /// Append domain separation bits to the message.  Unlike `update_bits`, these are not
+    /// counted as absorbed message bits.
+    pub(crate) fn append_suffix(&mut self, suffix: &BitSlice<u8, Lsb0>) -> Result<()> {
+        if self.finalized {
+            Err(Sha3Error::Finalized.into())
+        } else {
+            self.check_rate()?;
+            self.message.extend_from_bitslice(suffix);
+            self.absorb_full_blocks()
+        }
+    }
+
+    /// The current state of the sponge.
+    #[cfg(feature = "low-level")]
+    #[must_use]
+    pub fn state(&self) -> &[u64; LANE_COUNT] {
+        &self.state
+    }
+
+    /// The number of output bits squeezed from the state but not yet returned.
+    ///
+    /// Each refill of the output buffer makes `rate` bits available, so after squeezing
+    /// `n` bits in total this is `k * rate - n` for the `k` refills performed so far.
+    #[cfg(feature = "low-level")]
+    #[must_use]
+    pub fn buffered_output_bits(&self) -> usize {
+        self.output.len()
+    }
+
+    /// The last `rate` bit block xored into the state, or `None` if no block has been
+    /// absorbed yet.
+    ///
+    /// After [`finalize`](Self::finalize), this is the final padded block: the end of the
+    /// message, the domain separation bits, and the `pad10*1` padding.  It is the place to look when a digest
+    /// differs from an expected value.
+    #[cfg(feature = "low-level")]
+    #[must_use]
+    pub fn last_padded_block(&self) -> Option<&BitSlice<u8, Lsb0>> {
+        self.last_block
+            .as_ref()
+            .map(|bytes| &BitSlice::from_slice(bytes)[..self.rate])
+    }
+
+    /// Absorb a single raw block of exactly `rate` bits into the sponge.
+    ///
+    /// The block is xor-ed into the state and the state is permuted, but no `pad10*1`
+    /// padding or domain separation bits are applied.  This is the building block for
+    /// custom padding schemes and experimental sponge modes.
+    ///
+    /// Raw blocks may only be absorbed on a block boundary, i.e. when everything given to
+    /// [`update`](Self::update) and [`update_bits`](Self::update_bits) so far fills whole
+    /// blocks, so they can never be reordered ahead of buffered message bits.
+    ///
+    /// # Errors
+    /// An error will be returned if the block is not exactly `rate` bits long, if a partial
+    /// message block is still buffered, or if the sponge has already been finalized.
+    ///
+    #[cfg(feature = "low-level")]
+    pub fn absorb_raw_block(&mut self, block: &BitSlice<u8, Lsb0>) -> Result<()> {
+        if self.finalized {
+            Err(Sha3Error::Finalized.into())
+        } else if self.rate > SHA3_WIDTH {
+            Err(Sha3Error::InvalidRate(self.rate).into())
+        } else if !self.message.is_empty() {
+            Err(Sha3Error::UnalignedRawBlock(self.message.len()).into())
+        } else if block.len() == self.rate {
+            let big_endian = self.big_endian();
+            xor_block(&mut self.state, block, big_endian);
+            self.last_block = Some(block_bytes(block));
+            self.keccak()
+        } else {
+            Err(Sha3Error::InvalidBlockLength(block.len(), self.rate).into())
+        }
+    }
+
+    /// Absorb `data` into the sponge, buffering any trailing partial block.
+    ///
+    /// # Errors
+    /// An error will be returned if the sponge has already been finalized.
+    ///
+    #[cfg(feature = "low-level")]
+    pub fn update(&mut self, data: &[u8]) -> Result<()> {
+        Sponge::update(self, data)
+    }
+
+    /// Absorb the bits of `data` into the sponge, buffering any trailing partial block.
+    ///
+    /// # Errors
+    /// An error will be returned if the sponge has already been finalized.
+    ///
+    #[cfg(feature = "low-level")]
+    pub fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()> {
+        Sponge::update_bits(self, data)
+    }
+
+    /// Append the domain separation `suffix` (e.g. `01` for SHA-3, `1111` for SHAKE, or
+    /// nothing for raw Keccak), apply `pad10*1` padding and absorb the final block.
+    ///
+    /// After this the sponge can only be squeezed.
+    ///
+    /// # Errors
+    /// An error will be returned if the sponge has already been finalized, or if the rate
+    /// is zero.
+    ///
+    #[cfg(feature = "low-level")]
+    pub fn finalize(&mut self, suffix: &BitSlice<u8, Lsb0>) -> Result<()> {
+        self.append_suffix(suffix)?;
+        Sponge::absorb(self)
+    }
+
+    /// Squeeze the next `output.len()` bytes from a finalized sponge.
+    ///
+    /// # Errors
+    /// An error will be returned if the sponge has not been finalized.
+    ///
+    #[cfg(feature = "low-level")]
+    pub fn squeeze_bytes(&mut self, output: &mut [u8]) -> Result<()> {
+        if self.finalized {
+            self.squeeze(output, output.len() * 8)
+        } else {
+            Err(Sha3Error::NotFinalized.into())
+        }
+    }
+
+    /// Squeeze the next `num_bits` bits from a finalized sponge, appending them to `output`.
+    ///
+    /// # Errors
+    /// An error will be returned if the sponge has not been finalized.
+    ///
+    #[cfg(feature = "low-level")]
+    pub fn squeeze_bits(&mut self, output: &mut BitVec<u8, Lsb0>, num_bits: usize) -> Result<()> {
+        if self.finalized {
+            self.squeeze_b(output, num_bits)
+        } else {
+            Err(Sha3Error::NotFinalized.into())
+        }
+    }
+
+    #[cfg(feature = "low-level")]
+    fn big_endian(&self) -> bool {
+        self.endian == Endian::Big
+    }
+
+    #[cfg(not(feature = "low-level"))]
+    #[allow(clippy::unused_self)]
+    fn big_endian(&self) -> bool {
+        false
+    }
+
+    // A block wider than the state cannot be xored into it
+    fn check_rate(&self) -> Result<()> {
+        if self.rate > SHA3_WIDTH {
+            Err(Sha3Error::InvalidRate(self.rate).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    // Absorb every complete `rate` bit block of the buffered message, leaving only the
+    // trailing partial block buffered.
+    fn absorb_full_blocks(&mut self) -> Result<()> {
+        if self.rate > 0 && self.message.len() >= self.rate {
+            let full = self.message.len() - self.message.len() % self.rate;
+            self.absorb_blocks(full)?;
+            // Drain in place so the buffer's allocation is kept for later updates
+            self.message.drain(..full).for_each(drop);
         }
         Ok(())
     }
 
-    fn squeeze_b(&mut self, output: &mut BitVec<u8, Lsb0>, requested_bits: usize) -> Result<()> {
+    // Absorb the first `len` bits of the buffered message, which must be a multiple of the
+    // rate.
+    fn absorb_blocks(&mut self, len: usize) -> Result<()> {
+        let big_endian = self.big_endian();
+        for block in self.message[..len].chunks_exact(self.rate) {
+            xor_block(&mut self.state, block, big_endian);
+            p_1600(&mut self.state, self.rounds)?;
+        }
+        #[cfg(feature = "low-level")]
+        if len > 0 {
+            self.last_block = Some(block_bytes(&self.message[len - self.rate..len]));
+        }
+        Ok(())
+    }
+
+    fn keccak(&mut self) -> Result<()> {
+        p_1600(&mut self.state, self.rounds)?;
+        Ok(())
+    }
+
+    fn fill_output(&mut self) {
+        self.output.clear();
+        for s in &self.state {
+            let bytes = if self.big_endian() {
+                s.to_be_bytes()
+            } else {
+                s.to_le_bytes()
+            };
+            self.output.extend_from_raw_slice(&bytes);
+        }
+        self.output.truncate(self.rate);
+    }
+
+    // The next output bit, permuting the state once the current block is exhausted
+    fn next_bit(&mut self) -> Result<bool> {
         if self.output.is_empty() {
             self.keccak()?;
             self.fill_output();
             self.output.reverse();
         }
-        let mut num_bits = requested_bits;
+        Ok(self.output.pop().unwrap_or_default())
+    }
 
-        while num_bits > 0 {
-            while let Some(bit) = self.output.pop() {
-                output.push(bit);
-                num_bits -= 1;
-                if num_bits == 0 {
-                    break;
-                }
-            }
-            if num_bits > 0 {
-                self.keccak()?;
-                self.fill_output();
-                self.output.reverse();
+    fn squeeze(&mut self, output: &mut [u8], num_bits: usize) -> Result<()> {
+        for byte in output.iter_mut().take(num_bits / 8) {
+            let mut value = 0u8;
+            for j in 0..8 {
+                value |= u8::from(self.next_bit()?) << j;
             }
+            *byte = value;
+        }
+        Ok(())
+    }
+
+    fn squeeze_b(&mut self, output: &mut BitVec<u8, Lsb0>, requested_bits: usize) -> Result<()> {
+        output.reserve(requested_bits);
+        for _ in 0..requested_bits {
+            output.push(self.next_bit()?);
         }
         Ok(())
     }
@@ -123,9 +484,11 @@ impl Sponge for Keccak1600Sponge {
         if self.finalized {
             Err(Sha3Error::Finalized.into())
         } else {
+            self.check_rate()?;
             // Update the internal state with the new data
             self.message.extend_from_raw_slice(data);
-            Ok(())
+            self.bits_absorbed += data.len() * 8;
+            self.absorb_full_blocks()
         }
     }
 
@@ -133,36 +496,20 @@ impl Sponge for Keccak1600Sponge {
         if self.finalized {
             Err(Sha3Error::Finalized.into())
         } else {
+            self.check_rate()?;
             // Update the internal state with the new bits
             self.message.extend_from_bitslice(data);
-            Ok(())
+            self.bits_absorbed += data.len();
+            self.absorb_full_blocks()
         }
     }
 
     fn absorb(&mut self) -> Result<()> {
-        // Process the absorbed message
-        let mut chunks = self.message.chunks_exact(self.rate);
-        let mut bvs = Vec::new();
-        for bits in &mut chunks {
-            let mut bv = bits.to_bitvec();
-            pad10star1(&mut bv, self.rate)?;
-            zero_pad(&mut bv, self.capacity);
-            bvs.push(bv);
-        }
-
-        let rem = chunks.remainder();
-
-        if !rem.is_empty() {
-            let mut bv = rem.to_bitvec();
-            pad10star1(&mut bv, self.rate)?;
-            zero_pad(&mut bv, self.capacity);
-            bvs.push(bv);
-        }
-
-        for bv in bvs {
-            self.xor_block(&bv)?;
-            self.keccak()?;
-        }
+        self.check_rate()?;
+        // Only a partial block remains buffered; pad it out to one or two full blocks
+        pad10star1(&mut self.message, self.rate)?;
+        self.absorb_blocks(self.message.len())?;
+        self.message.clear();
 
         if self.output.is_empty() {
             self.fill_output();
@@ -173,7 +520,8 @@ impl Sponge for Keccak1600Sponge {
     }
 
     fn squeeze(&mut self, output: &mut [u8], num_bits: usize) -> Result<()> {
-        if output.len() == num_bits / 8 {
+        // Reject mismatched (or overflowing) lengths before squeezing anything
+        if output.len().checked_mul(8) == Some(num_bits) {
             self.squeeze(output, num_bits)
         } else {
             Err(Sha3Error::OutputLengthMismatch(output.len(), num_bits / 8).into())
@@ -185,26 +533,75 @@ impl Sponge for Keccak1600Sponge {
     }
 }
 
+// Pad a partial block (fewer than `rate_bits` bits) to a multiple of `rate_bits` with
+// 10*1 padding.  The padding is at least 2 bits, so a block of `rate_bits - 1` bits is
+// padded out to two full blocks.
 fn pad10star1(bits: &mut BitVec<u8, Lsb0>, rate_bits: usize) -> Result<()> {
-    let len = bits.len();
-    if len < rate_bits {
-        let len = isize::try_from(len)?;
-        let j = (-len - 2).rem_euclid(isize::try_from(rate_bits)?);
-
-        bits.push(true);
+    let j = pad_zero_count(bits.len(), rate_bits)?;
 
-        for _ in 0..j {
-            bits.push(false);
-        }
+    bits.push(true);
+    bits.resize(bits.len() + j, false);
+    bits.push(true);
+    Ok(())
+}
 
-        bits.push(true);
+// The number of zeros in the `pad10*1` padding of a `message_bits` long message
+fn pad_zero_count(message_bits: usize, rate_bits: usize) -> Result<usize> {
+    if rate_bits == 0 {
+        return Err(Sha3Error::InvalidRate(rate_bits).into());
     }
-    Ok(())
+    let len = isize::try_from(message_bits)?;
+    Ok(usize::try_from(
+        (-len - 2).rem_euclid(isize::try_from(rate_bits)?),
+    )?)
+}
+
+/// Compute the `pad10*1` padding bits that would be appended to a message of
+/// `message_bits` bits for a sponge with a rate of `rate` bits.
+///
+/// The padding is a `1`, the run of `0`s needed to reach a multiple of the rate, and a
+/// final `1`, so it is between 2 and `rate + 1` bits long.  Any domain separation bits
+/// (e.g. `01` for SHA-3) are part of the message here, so for SHA3-256 pass the message
+/// length plus 2.
+///
+/// # Errors
+/// An error will be returned if `rate` is zero.
+///
+#[cfg(feature = "low-level")]
+pub fn compute_padding(message_bits: usize, rate: usize) -> Result<BitVec<u8, Lsb0>> {
+    let mut padding = BitVec::with_capacity(rate + 1);
+    padding.push(true);
+    padding.resize(pad_zero_count(message_bits, rate)? + 1, false);
+    padding.push(true);
+    Ok(padding)
 }
 
-fn zero_pad(bits: &mut BitVec<u8, Lsb0>, capacity_bits: usize) {
-    let zero_buffer = vec![0u8; capacity_bits / 8];
-    bits.extend_from_raw_slice(&zero_buffer);
+// Copy a block of at most 1600 bits into a fixed size buffer, so recording the last
+// absorbed block does not allocate
+#[cfg(feature = "low-level")]
+fn block_bytes(block: &BitSlice<u8, Lsb0>) -> [u8; SHA3_WIDTH / 8] {
+    let mut bytes = [0u8; SHA3_WIDTH / 8];
+    BitSlice::<u8, Lsb0>::from_slice_mut(&mut bytes)[..block.len()].copy_from_bitslice(block);
+    bytes
+}
+
+// xor a block of message bits into the leading lanes of the state
+//
+// Lanes are loaded explicitly little-endian (FIPS 202 §B.1): byte j of a lane is bits
+// 8j..8j + 8 of its 64 bit chunk, least significant bit first, so the state does not
+// depend on the byte order of the host.
+fn xor_block(state: &mut [u64; LANE_COUNT], bits: &BitSlice<u8, Lsb0>, big_endian: bool) {
+    for (s, chunk) in state.iter_mut().zip(bits.chunks(64)) {
+        let mut bytes = [0u8; 8];
+        for (byte, byte_bits) in bytes.iter_mut().zip(chunk.chunks(8)) {
+            *byte = byte_bits.load_le::<u8>();
+        }
+        *s ^= if big_endian {
+            u64::from_be_bytes(bytes)
+        } else {
+            u64::from_le_bytes(bytes)
+        };
+    }
 }
 
 #[cfg(test)]
@@ -217,4 +614,153 @@ mod test {
         assert_eq!(sponge.capacity, 0);
         assert_eq!(sponge.rate, 0);
     }
+
+    #[test]
+    fn test_squeeze_output_length_mismatch() -> anyhow::Result<()> {
+        use crate::{
+            constants::{SHA3_256_CAPACITY, SHA3_256_RATE},
+            traits::Sponge,
+        };
+
+        let mut sponge = Keccak1600Sponge::new(SHA3_256_RATE, SHA3_256_CAPACITY);
+        sponge.absorb()?;
+        let mut output = [0u8; 4];
+        assert!(Sponge::squeeze(&mut sponge, &mut output, usize::MAX).is_err());
+        assert!(Sponge::squeeze(&mut sponge, &mut output, 33).is_err());
+        assert!(Sponge::squeeze(&mut sponge, &mut output, 24).is_err());
+        Sponge::squeeze(&mut sponge, &mut output, 32)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "low-level")]
+    #[test]
+    fn test_absorb_raw_block() -> anyhow::Result<()> {
+        use bitvec::{order::Lsb0, vec::BitVec};
+
+        use crate::{
+            constants::{LANE_COUNT, SHA3_256_CAPACITY, SHA3_256_RATE},
+            f_1600,
+        };
+
+        let block_bytes = (0..SHA3_256_RATE / 8)
+            .map(|i| u8::try_from(i % 256))
+            .collect::<Result<Vec<u8>, _>>()?;
+        let block = BitVec::<u8, Lsb0>::from_slice(&block_bytes);
+
+        let mut expected = [0u64; LANE_COUNT];
+        for (lane, bytes) in expected.iter_mut().zip(block_bytes.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes(bytes.try_into()?);
+        }
+        f_1600(&mut expected)?;
+
+        let mut sponge = Keccak1600Sponge::new(SHA3_256_RATE, SHA3_256_CAPACITY);
+        sponge.absorb_raw_block(&block)?;
+        assert_eq!(&expected, sponge.state());
+
+        f_1600(&mut expected)?;
+        sponge.absorb_raw_block(&BitVec::<u8, Lsb0>::repeat(false, SHA3_256_RATE))?;
+        assert_eq!(&expected, sponge.state());
+        Ok(())
+    }
+
+    #[cfg(feature = "low-level")]
+    #[test]
+    fn test_new_bytes_converts_to_bits() -> anyhow::Result<()> {
+        use crate::constants::{SHA3_256_CAPACITY, SHA3_256_RATE};
+
+        let sponge = Keccak1600Sponge::new_bytes(136, 64)?;
+        assert_eq!(SHA3_256_RATE, sponge.rate);
+        assert_eq!(SHA3_256_CAPACITY, sponge.capacity);
+        Ok(())
+    }
+
+    #[cfg(feature = "low-level")]
+    #[test]
+    fn test_new_bytes_invalid_parameters() {
+        assert!(Keccak1600Sponge::new_bytes(1088, 512).is_err());
+        assert!(Keccak1600Sponge::new_bytes(136, 63).is_err());
+        assert!(Keccak1600Sponge::new_bytes(usize::MAX, 64).is_err());
+    }
+
+    #[cfg(feature = "low-level")]
+    #[test]
+    fn test_absorb_raw_block_wrong_length_error() {
+        use bitvec::{order::Lsb0, vec::BitVec};
+
+        use crate::constants::{SHA3_256_CAPACITY, SHA3_256_RATE};
+
+        let mut sponge = Keccak1600Sponge::new(SHA3_256_RATE, SHA3_256_CAPACITY);
+        let short = BitVec::<u8, Lsb0>::repeat(false, SHA3_256_RATE - 1);
+        let long = BitVec::<u8, Lsb0>::repeat(false, SHA3_256_RATE + 1);
+        assert!(sponge.absorb_raw_block(&short).is_err());
+        assert!(sponge.absorb_raw_block(&long).is_err());
+        assert_eq!(&[0u64; 25], sponge.state());
+    }
+
+    #[cfg(feature = "low-level")]
+    #[test]
+    fn test_compute_padding_edge_cases() -> anyhow::Result<()> {
+        use bitvec::{bits, order::Lsb0};
+
+        use super::compute_padding;
+        use crate::constants::SHA3_256_RATE;
+
+        // Two bits short of the rate: the padding is just the two `1` bits
+        let padding = compute_padding(SHA3_256_RATE - 2, SHA3_256_RATE)?;
+        assert_eq!(bits![u8, Lsb0; 1, 1], padding.as_bitslice());
+        // One bit short of the rate: the padding spills into a whole extra block
+        let padding = compute_padding(SHA3_256_RATE - 1, SHA3_256_RATE)?;
+        assert_eq!(SHA3_256_RATE + 1, padding.len());
+        // An empty message is padded out to a single full block
+        let padding = compute_padding(0, SHA3_256_RATE)?;
+        assert_eq!(SHA3_256_RATE, padding.len());
+        assert!(padding[0] && padding[SHA3_256_RATE - 1]);
+        assert_eq!(2, padding.count_ones());
+        assert!(compute_padding(0, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_xor_block_lanes_are_little_endian() {
+        use bitvec::{order::Lsb0, slice::BitSlice};
+
+        use super::xor_block;
+        use crate::constants::LANE_COUNT;
+
+        let block = (0u8..136)
+            .map(|i| i.wrapping_mul(0x9d))
+            .collect::<Vec<u8>>();
+        let mut state = [0u64; LANE_COUNT];
+        xor_block(&mut state, BitSlice::<u8, Lsb0>::from_slice(&block), false);
+
+        // Compose each lane arithmetically, so the expectation holds on any host: byte j
+        // of the lane is the coefficient of 2^(8j)
+        for (lane, bytes) in state.iter().zip(block.chunks_exact(8)) {
+            let expected = bytes
+                .iter()
+                .rev()
+                .fold(0u64, |acc, &byte| (acc << 8) | u64::from(byte));
+            assert_eq!(expected, *lane);
+        }
+        assert_eq!(0x9d, (state[0] >> 8) & 0xff);
+        assert!(state[17..].iter().all(|&lane| lane == 0));
+
+        // A partial final lane fills its low bytes
+        let mut state = [0u64; LANE_COUNT];
+        xor_block(
+            &mut state,
+            &BitSlice::<u8, Lsb0>::from_slice(&[0x01, 0x02, 0x03])[..20],
+            false,
+        );
+        assert_eq!(0x0003_0201, state[0]);
+
+        // The big-endian packing reverses the bytes of each lane
+        let mut big = [0u64; LANE_COUNT];
+        xor_block(&mut big, BitSlice::<u8, Lsb0>::from_slice(&block), true);
+        let mut little = [0u64; LANE_COUNT];
+        xor_block(&mut little, BitSlice::<u8, Lsb0>::from_slice(&block), false);
+        for (big, little) in big.iter().zip(little) {
+            assert_eq!(little.swap_bytes(), *big);
+        }
+    }
 }
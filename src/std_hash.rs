@@ -0,0 +1,145 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! A `std::hash::Hasher` adapter for SHA3-256
+
+use std::hash::{BuildHasher, Hasher as StdHasher};
+
+use crate::{Hasher, SHA3_256_BYTES, Sha3_256};
+
+// `std::hash::Hasher` cannot report errors.  A SHA3-256 hasher only fails once it has
+// been finalized, and `Sha3Hasher` never finalizes `inner` itself (`finish` finalizes a
+// clone), so its updates and finalizes cannot fail.
+const NEVER_FINALIZED: &str = "the inner SHA3-256 hasher is never finalized";
+
+/// A [`std::hash::Hasher`] backed by SHA3-256, for keying a `HashMap` or `HashSet`.
+///
+/// `finish()` returns the first 8 bytes of the SHA3-256 digest of everything written,
+/// interpreted as a **little-endian** `u64`.  Integers written through the `write_*`
+/// methods are absorbed as their little-endian bytes, and `usize` values are widened to
+/// `u64` first, so a given input yields the same `u64` on every platform regardless of
+/// endianness or pointer width.  This makes the result suitable for persisted hash-based
+/// structures.
+///
+/// Note that `Hash` implementations are free to write their data in any way, so the
+/// guarantee covers the bytes and integers written, not every `Hash` type.
+///
+/// ```
+/// # use shashasha::Sha3BuildHasher;
+/// # use std::collections::HashMap;
+/// let mut map = HashMap::with_hasher(Sha3BuildHasher);
+/// let _ = map.insert("key", 1);
+/// assert_eq!(Some(&1), map.get("key"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sha3Hasher {
+    inner: Sha3_256,
+}
+
+impl Default for Sha3Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha3Hasher {
+    /// Create a new hasher.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: Sha3_256::new(),
+        }
+    }
+}
+
+impl StdHasher for Sha3Hasher {
+    fn finish(&self) -> u64 {
+        let mut digest = [0u8; SHA3_256_BYTES];
+        let mut first = [0u8; 8];
+        self.inner
+            .clone()
+            .finalize(&mut digest)
+            .expect(NEVER_FINALIZED);
+        first.copy_from_slice(&digest[..8]);
+        u64::from_le_bytes(first)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.update(bytes).expect(NEVER_FINALIZED);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+/// A [`BuildHasher`] creating [`Sha3Hasher`]s, for `HashMap::with_hasher`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha3BuildHasher;
+
+impl BuildHasher for Sha3BuildHasher {
+    type Hasher = Sha3Hasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        Sha3Hasher::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::hash::{BuildHasher, Hasher};
+
+    use super::{Sha3BuildHasher, Sha3Hasher};
+
+    #[test]
+    fn test_finish_is_pinned_little_endian() {
+        // The first 8 bytes of SHA3-256("Hello, world!") are f3 45 a2 19 da 00 5e be
+        let mut hasher = Sha3Hasher::new();
+        hasher.write(b"Hello, world!");
+        assert_eq!(0xbe5e_00da_19a2_45f3, hasher.finish());
+        assert_eq!(13_717_402_451_750_045_171, hasher.finish());
+    }
+
+    #[test]
+    fn test_integers_are_written_little_endian() {
+        let mut ints = Sha3Hasher::new();
+        ints.write_u16(0x0201);
+        ints.write_u32(0x0605_0403);
+        ints.write_u64(0x0e0d_0c0b_0a09_0807);
+        ints.write_usize(0x0f);
+        ints.write_i8(0x10);
+
+        let mut bytes = Sha3Hasher::new();
+        bytes.write(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        bytes.write(&[0x0f, 0, 0, 0, 0, 0, 0, 0, 0x10]);
+        assert_eq!(bytes.finish(), ints.finish());
+    }
+
+    #[test]
+    fn test_build_hasher_is_deterministic() {
+        let build = Sha3BuildHasher;
+        assert_eq!(Sha3BuildHasher.hash_one("key"), build.hash_one("key"));
+        assert_ne!(build.hash_one("key"), build.hash_one("other key"));
+    }
+}
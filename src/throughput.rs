@@ -0,0 +1,77 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! In-process throughput measurement
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::DynHasher;
+
+// Hashing is done in chunks of this size, which divides a MiB evenly.  It is not a multiple
+// of the SHA-3 rates, so most updates leave a partial block buffered, as in typical use.
+const CHUNK_BYTES: usize = 64 * 1024;
+const MIB: usize = 1024 * 1024;
+
+/// Hash `mib` MiB of a fixed byte pattern with `hasher`, finalize it, and return the
+/// elapsed wall-clock time.
+///
+/// This is not a benchmark harness: there is no warm up and a single run is timed.  It
+/// is meant for comparing builds (e.g. with and without the `unroll` feature) on the
+/// machine they will be deployed to.  Throughput in MiB/s is `mib` divided by the
+/// returned duration in seconds.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{Sha3_256, measure_throughput};
+/// # pub fn main() -> Result<()> {
+/// let elapsed = measure_throughput(&mut Sha3_256::new(), 1)?;
+/// println!("SHA3-256: {:.1} MiB/s", 1.0 / elapsed.as_secs_f64());
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// An error will be returned if the hasher has already been finalized.
+///
+pub fn measure_throughput<H: DynHasher + ?Sized>(hasher: &mut H, mib: usize) -> Result<Duration> {
+    let chunk = (0..=u8::MAX).cycle().take(CHUNK_BYTES).collect::<Vec<u8>>();
+    let start = Instant::now();
+    for _ in 0..mib * (MIB / CHUNK_BYTES) {
+        hasher.update(&chunk)?;
+    }
+    let _digest = hasher.finalize_to_vec()?;
+    Ok(start.elapsed())
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::measure_throughput;
+    use crate::{DynHasher, SHA3_224_BYTES, Sha3_224};
+
+    #[test]
+    fn test_measure_throughput() -> Result<()> {
+        let mut hasher = Sha3_224::new();
+        assert!(measure_throughput(&mut hasher, 1)? > std::time::Duration::ZERO);
+        // The hasher was finalized by the measurement
+        assert!(DynHasher::update(&mut hasher, b"").is_err());
+        assert!(measure_throughput(&mut hasher, 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_measure_throughput_dyn() -> Result<()> {
+        let mut hasher: Box<dyn DynHasher> = Box::new(Sha3_224::new());
+        let _ = measure_throughput(hasher.as_mut(), 0)?;
+        assert_eq!(SHA3_224_BYTES, hasher.output_len());
+        Ok(())
+    }
+}
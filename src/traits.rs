@@ -6,9 +6,20 @@
 // option. All files in the project carrying such notice may not be copied,
 // modified, or distributed except according to those terms.
 
+use std::io::{self, IoSlice, Read, Write};
+
 use anyhow::Result;
 use bitvec::{order::Lsb0, slice::BitSlice, vec::BitVec};
 
+use crate::{
+    AlignedBytes, Sha3Error,
+    absorb::{self, BLOCK_BYTES},
+    constants::MIN_TAG_BYTES,
+    utils::{ct_eq, hex_to_bytes},
+};
+#[cfg(feature = "tokio")]
+use tokio::io::AsyncRead;
+
 /// Trait for hashing data with a fixed output size and byte input.
 pub trait Hasher<const D_BYTES: usize> {
     /// Update the hasher with new byte data.
@@ -17,11 +28,280 @@ pub trait Hasher<const D_BYTES: usize> {
     /// An error will be returned if `update` is called after the hasher has been finalized.
     ///
     fn update(&mut self, data: &[u8]) -> Result<()>;
+    /// Update the hasher with anything that can be viewed as bytes, e.g. a `&str`,
+    /// `String`, `Vec<u8>` or byte array.
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use shashasha::{Hasher, SHA3_256_BYTES, Sha3_256};
+    /// # pub fn main() -> Result<()> {
+    /// let mut hasher = Sha3_256::new();
+    /// hasher.update_any(String::from("Hello, "))?;
+    /// hasher.update_any(vec![b'w', b'o'])?;
+    /// hasher.update_any([b'r', b'l', b'd'])?;
+    /// hasher.update_any("!")?;
+    /// let mut result = [0u8; SHA3_256_BYTES];
+    /// hasher.finalize(&mut result)?;
+    ///
+    /// let mut expected = Sha3_256::new();
+    /// expected.update(String::from("Hello, ").as_bytes())?;
+    /// expected.update(vec![b'w', b'o'].as_slice())?;
+    /// expected.update(&[b'r', b'l', b'd'])?;
+    /// expected.update("!".as_bytes())?;
+    /// let mut expected_result = [0u8; SHA3_256_BYTES];
+    /// expected.finalize(&mut expected_result)?;
+    /// assert_eq!(expected_result, result);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// An error will be returned if `update_any` is called after the hasher has been finalized.
+    ///
+    fn update_any<T: AsRef<[u8]>>(&mut self, data: T) -> Result<()>
+    where
+        Self: Sized,
+    {
+        self.update(data.as_ref())
+    }
+    /// Update the hasher with each buffer of a vectored I/O slice, in order.
+    ///
+    /// The result is the same as a single update with the concatenation of the buffers,
+    /// without copying them into one.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    fn update_io_slices(&mut self, bufs: &[IoSlice<'_>]) -> Result<()> {
+        absorb::io_slices(|data| self.update(data), bufs)
+    }
+    /// Update the hasher with `count` copies of `byte`.
+    ///
+    /// The result is the same as `update(&vec![byte; count])`, but the bytes are absorbed
+    /// from a small fixed-size buffer, so memory use does not grow with `count`.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    fn update_repeated(&mut self, byte: u8, count: usize) -> Result<()> {
+        absorb::repeated(|data| self.update(data), byte, count)
+    }
+    /// Decode the hex string `s` and update the hasher with the resulting bytes.
+    ///
+    /// Whitespace is ignored and both upper and lower case digits are allowed, as with
+    /// [`bits_from_hex`](crate::bits_from_hex).
+    ///
+    /// # Errors
+    /// An error will be returned if `s` is not valid hex, or if the hasher has already been
+    /// finalized.
+    ///
+    fn update_hex(&mut self, s: &str) -> Result<()> {
+        absorb::hex(|data| self.update(data), s)
+    }
+    /// Update the hasher with the 4 little-endian bytes of `value`.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_u32_le` is called after the hasher has been finalized.
+    ///
+    fn update_u32_le(&mut self, value: u32) -> Result<()> {
+        absorb::u32_le(|data| self.update(data), value)
+    }
+    /// Update the hasher with the 4 big-endian bytes of `value`.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_u32_be` is called after the hasher has been finalized.
+    ///
+    fn update_u32_be(&mut self, value: u32) -> Result<()> {
+        absorb::u32_be(|data| self.update(data), value)
+    }
+    /// Update the hasher with the 8 little-endian bytes of `value`.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_u64_le` is called after the hasher has been finalized.
+    ///
+    fn update_u64_le(&mut self, value: u64) -> Result<()> {
+        absorb::u64_le(|data| self.update(data), value)
+    }
+    /// Update the hasher with the 8 big-endian bytes of `value`.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_u64_be` is called after the hasher has been finalized.
+    ///
+    fn update_u64_be(&mut self, value: u64) -> Result<()> {
+        absorb::u64_be(|data| self.update(data), value)
+    }
+    /// Update the hasher with the LEB128 varint encoding of `value`, as used for protobuf
+    /// field lengths and integers, e.g. `300` is absorbed as `[0xAC, 0x02]`.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_varint` is called after the hasher has been finalized.
+    ///
+    fn update_varint(&mut self, value: u64) -> Result<()> {
+        absorb::leb128(|data| self.update(data), value)
+    }
+    /// Update the hasher with the rows of a byte matrix, recording its shape.
+    ///
+    /// The matrix is absorbed as `left_encode(rows.len())` followed by
+    /// `left_encode(row.len()) || row` for each row, with lengths in bytes, so matrices of
+    /// different shapes never hash the same even when their concatenated rows are equal.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_matrix` is called after the hasher has been finalized.
+    ///
+    fn update_matrix(&mut self, rows: &[&[u8]]) -> Result<()> {
+        absorb::matrix(|data| self.update(data), rows)
+    }
+    /// Update the hasher with `data`, then zero the caller's buffer.
+    ///
+    /// This is intended for sensitive input such as a password.  The buffer is zeroed even
+    /// if the update fails.  Note that the hasher itself buffers any trailing partial block
+    /// of the input until it is finalized.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    #[cfg(feature = "zeroize")]
+    fn update_and_zeroize(&mut self, data: &mut [u8]) -> Result<()> {
+        absorb::and_zeroize(|data| Hasher::update(self, data), data)
+    }
+    /// Perform a single read of up to `max_bytes` bytes from `reader` and absorb whatever
+    /// was read, returning the number of bytes absorbed.
+    ///
+    /// At most 8 KiB is read per call whatever `max_bytes` is, so `usize::MAX` can be
+    /// passed for "no limit" without allocating it.
+    ///
+    /// Exactly one [`Read::read`] call is made, so partial reads are absorbed as they
+    /// arrive and the caller drives the loop; a return value of `0` (with `max_bytes > 0`)
+    /// signals end of input.  Read errors, including [`io::ErrorKind::WouldBlock`] and
+    /// [`io::ErrorKind::Interrupted`], are returned to the caller without absorbing anything,
+    /// so the call can simply be retried.
+    ///
+    /// # Errors
+    /// An error will be returned if reading from `reader` fails, or if the hasher has already
+    /// been finalized.
+    ///
+    fn update_read_some<R: Read>(&mut self, reader: &mut R, max_bytes: usize) -> io::Result<usize>
+    where
+        Self: Sized,
+    {
+        absorb::read_some(|data| Hasher::update(self, data), reader, max_bytes)
+    }
+    /// Write all of `data` to `sink` and absorb it, so data can be hashed while it is
+    /// forwarded (e.g. to a file) without a second pass.
+    ///
+    /// `data` is written first, so if the write fails nothing is absorbed and the call can
+    /// be retried, although `sink` may have received part of `data`.
+    ///
+    /// # Errors
+    /// An error will be returned if writing to `sink` fails, or if the hasher has already
+    /// been finalized.
+    ///
+    fn update_tee<W: Write>(&mut self, data: &[u8], sink: &mut W) -> io::Result<()>
+    where
+        Self: Sized,
+    {
+        absorb::tee(|data| Hasher::update(self, data), data, sink)
+    }
+    /// Read `reader` to the end and absorb everything read, yielding to the runtime at
+    /// every read.
+    ///
+    /// The result is the same as absorbing the same bytes synchronously.  The returned
+    /// future is `Send` so it can be spawned on a multi-threaded runtime.
+    ///
+    /// # Errors
+    /// An error will be returned if reading from `reader` fails, or if the hasher has already
+    /// been finalized.  Bytes read before the error have already been absorbed.
+    ///
+    #[cfg(feature = "tokio")]
+    fn update_async<R>(&mut self, reader: &mut R) -> impl Future<Output = io::Result<()>> + Send
+    where
+        Self: Send + Sized,
+        R: AsyncRead + Unpin + Send,
+    {
+        absorb::read_to_end(|data| Hasher::update(self, data), reader)
+    }
     /// Finalize the hash computation and return the result.
     ///
     /// # Errors
     ///
     fn finalize(&mut self, output: &mut [u8; D_BYTES]) -> Result<()>;
+    /// Finalize the hash computation and compare the digest with the hex string
+    /// `expected_hex`.
+    ///
+    /// The expected digest is parsed as with [`bits_from_hex`](crate::bits_from_hex), so
+    /// whitespace is ignored and either case is accepted.  The comparison does not exit
+    /// early on the first differing byte, and an expected digest of the wrong length is
+    /// simply not equal.
+    ///
+    /// # Errors
+    /// An error will be returned if `expected_hex` is not valid hex, in which case the
+    /// hasher is not finalized, or if the hasher has already been finalized.
+    ///
+    fn finalize_equals_hex(&mut self, expected_hex: &str) -> Result<bool> {
+        let expected = hex_to_bytes(expected_hex)?;
+        let mut digest = [0u8; D_BYTES];
+        self.finalize(&mut digest)?;
+        Ok(ct_eq(&digest, &expected))
+    }
+    /// Finalize the hash computation and return the leftmost `tag_len` bytes of the digest
+    /// as a tag.
+    ///
+    /// `tag_len` must be at least [`MIN_TAG_BYTES`](crate::MIN_TAG_BYTES) (16 bytes, for
+    /// 128 bits of security) and at most the digest size, so a dangerously short tag
+    /// cannot be produced by accident.
+    ///
+    /// # Errors
+    /// An error will be returned if `tag_len` is shorter than the minimum or longer than
+    /// the digest, or if the hasher has already been finalized.
+    ///
+    fn finalize_tag(&mut self, tag_len: usize) -> Result<Vec<u8>> {
+        if tag_len < MIN_TAG_BYTES {
+            Err(Sha3Error::TagTooShort(tag_len, MIN_TAG_BYTES).into())
+        } else if tag_len > D_BYTES {
+            Err(Sha3Error::OutputLengthMismatch(tag_len, D_BYTES).into())
+        } else {
+            let mut output = [0u8; D_BYTES];
+            self.finalize(&mut output)?;
+            Ok(output[..tag_len].to_vec())
+        }
+    }
+    /// Finalize the hash computation and return the result as bits.
+    ///
+    /// The digest bits are exactly `BitVec::<u8, Lsb0>::from_slice(&output)` for the
+    /// `output` array that [`finalize`](Self::finalize) would have produced, i.e. bit `i`
+    /// of the result is bit `i % 8` of byte `i / 8`, so the two representations can be
+    /// converted between without loss.
+    ///
+    /// # Errors
+    /// An error will be returned if `digest_bits` is called after the hasher has been finalized.
+    ///
+    fn digest_bits(&mut self) -> Result<BitVec<u8, Lsb0>> {
+        let mut output = [0u8; D_BYTES];
+        self.finalize(&mut output)?;
+        Ok(BitVec::from_slice(&output))
+    }
+}
+
+/// Object safe trait for hashing data with a fixed output size chosen at runtime.
+///
+/// [`Hasher`] is generic over the digest size, so it cannot be used as a trait object.
+/// `DynHasher` is implemented for each SHA3 hasher and can be stored as a
+/// `Box<dyn DynHasher>` when the algorithm is only known at runtime.
+pub trait DynHasher {
+    /// Update the hasher with new byte data.
+    ///
+    /// # Errors
+    /// An error will be returned if `update` is called after the hasher has been finalized.
+    ///
+    fn update(&mut self, data: &[u8]) -> Result<()>;
+    /// Finalize the hash computation and return the digest.
+    ///
+    /// # Errors
+    /// An error will be returned if `finalize_to_vec` is called after the hasher has been finalized.
+    ///
+    fn finalize_to_vec(&mut self) -> Result<Vec<u8>>;
+    /// The size of the digest in bytes.
+    fn output_len(&self) -> usize;
 }
 
 /// Trait for hashing data with a fixed output size and `BitSlice` input.
@@ -32,6 +312,68 @@ pub trait HasherBits<const D_BYTES: usize> {
     /// An error will be returned if `update_bits` is called after the hasher has been finalized.
     ///
     fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()>;
+    /// Update the hasher with each chunk of bits produced by `iter`, in order.
+    ///
+    /// Chunks need not be byte-aligned; the result is the same as a single
+    /// [`update_bits`](Self::update_bits) call with the concatenation of every chunk.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    fn update_bits_iter<'a, I>(&mut self, iter: I) -> Result<()>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = &'a BitSlice<u8, Lsb0>>,
+    {
+        absorb::bits_iter(|bits| HasherBits::update_bits(self, bits), iter)
+    }
+    /// Read `total_bits.div_ceil(8)` bytes from `reader` and absorb exactly the first
+    /// `total_bits` of those bits.
+    ///
+    /// Bits are taken least significant bit first from each byte, as with
+    /// [`update_bits`](Self::update_bits), so the unused high bits of the final byte are
+    /// discarded.  This allows hashing a bitstream whose length is not a multiple of 8,
+    /// e.g. a bit-length-prefixed frame read from a socket.  The bytes are read and
+    /// absorbed one block at a time, so memory use does not depend on `total_bits`.
+    ///
+    /// # Errors
+    /// An error will be returned if `reader` ends before all of the bytes have been read,
+    /// if reading from `reader` fails, or if the hasher has already been finalized.  Any
+    /// blocks read before the error have already been absorbed.
+    ///
+    fn update_reader_bits<R: Read>(&mut self, reader: &mut R, total_bits: usize) -> io::Result<()>
+    where
+        Self: Sized,
+    {
+        absorb::reader_bits(
+            |bits| HasherBits::update_bits(self, bits),
+            reader,
+            total_bits,
+        )
+    }
+    /// Absorb `full_bytes` followed by the low `trailing_bits` bits of `trailing`.
+    ///
+    /// This hashes a message such as "3 full bytes plus 5 more bits" without building a
+    /// [`BitVec`].  As with [`update_bits`](Self::update_bits), bits are taken least
+    /// significant bit first, so the unused high bits of `trailing` are ignored.
+    ///
+    /// # Errors
+    /// An error will be returned if `trailing_bits` is greater than 8, or if the hasher has
+    /// already been finalized.
+    ///
+    fn update_with_trailing_bits(
+        &mut self,
+        full_bytes: &[u8],
+        trailing: u8,
+        trailing_bits: usize,
+    ) -> Result<()> {
+        absorb::trailing_bits(
+            |bits| HasherBits::update_bits(self, bits),
+            full_bytes,
+            trailing,
+            trailing_bits,
+        )
+    }
 }
 
 /// Trait for hashing data with an arbitrary output size and byte input data.
@@ -42,16 +384,350 @@ pub trait XofHasher {
     /// An error will be returned if `update` is called after the hasher has been finalized.
     ///
     fn update(&mut self, data: &[u8]) -> Result<()>;
+    /// Update the hasher with anything that can be viewed as bytes, e.g. a `&str`,
+    /// `String`, `Vec<u8>` or byte array.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_any` is called after the hasher has been finalized.
+    ///
+    fn update_any<T: AsRef<[u8]>>(&mut self, data: T) -> Result<()>
+    where
+        Self: Sized,
+    {
+        self.update(data.as_ref())
+    }
+    /// Update the hasher with each buffer of a vectored I/O slice, in order.
+    ///
+    /// The result is the same as a single update with the concatenation of the buffers,
+    /// without copying them into one.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    fn update_io_slices(&mut self, bufs: &[IoSlice<'_>]) -> Result<()> {
+        absorb::io_slices(|data| self.update(data), bufs)
+    }
+    /// Update the hasher with `count` copies of `byte`.
+    ///
+    /// The result is the same as `update(&vec![byte; count])`, but the bytes are absorbed
+    /// from a small fixed-size buffer, so memory use does not grow with `count`.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    fn update_repeated(&mut self, byte: u8, count: usize) -> Result<()> {
+        absorb::repeated(|data| self.update(data), byte, count)
+    }
+    /// Decode the hex string `s` and update the hasher with the resulting bytes.
+    ///
+    /// Whitespace is ignored and both upper and lower case digits are allowed, as with
+    /// [`bits_from_hex`](crate::bits_from_hex).
+    ///
+    /// # Errors
+    /// An error will be returned if `s` is not valid hex, or if the hasher has already been
+    /// finalized.
+    ///
+    fn update_hex(&mut self, s: &str) -> Result<()> {
+        absorb::hex(|data| self.update(data), s)
+    }
+    /// Update the hasher with the 4 little-endian bytes of `value`.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_u32_le` is called after the hasher has been finalized.
+    ///
+    fn update_u32_le(&mut self, value: u32) -> Result<()> {
+        absorb::u32_le(|data| self.update(data), value)
+    }
+    /// Update the hasher with the 4 big-endian bytes of `value`.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_u32_be` is called after the hasher has been finalized.
+    ///
+    fn update_u32_be(&mut self, value: u32) -> Result<()> {
+        absorb::u32_be(|data| self.update(data), value)
+    }
+    /// Update the hasher with the 8 little-endian bytes of `value`.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_u64_le` is called after the hasher has been finalized.
+    ///
+    fn update_u64_le(&mut self, value: u64) -> Result<()> {
+        absorb::u64_le(|data| self.update(data), value)
+    }
+    /// Update the hasher with the 8 big-endian bytes of `value`.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_u64_be` is called after the hasher has been finalized.
+    ///
+    fn update_u64_be(&mut self, value: u64) -> Result<()> {
+        absorb::u64_be(|data| self.update(data), value)
+    }
+    /// Update the hasher with the LEB128 varint encoding of `value`, as used for protobuf
+    /// field lengths and integers, e.g. `300` is absorbed as `[0xAC, 0x02]`.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_varint` is called after the hasher has been finalized.
+    ///
+    fn update_varint(&mut self, value: u64) -> Result<()> {
+        absorb::leb128(|data| self.update(data), value)
+    }
+    /// Update the hasher with the rows of a byte matrix, recording its shape.
+    ///
+    /// The matrix is absorbed as `left_encode(rows.len())` followed by
+    /// `left_encode(row.len()) || row` for each row, with lengths in bytes, so matrices of
+    /// different shapes never hash the same even when their concatenated rows are equal.
+    ///
+    /// # Errors
+    /// An error will be returned if `update_matrix` is called after the hasher has been finalized.
+    ///
+    fn update_matrix(&mut self, rows: &[&[u8]]) -> Result<()> {
+        absorb::matrix(|data| self.update(data), rows)
+    }
+    /// Update the hasher with `data`, then zero the caller's buffer.
+    ///
+    /// This is intended for sensitive input such as a password.  The buffer is zeroed even
+    /// if the update fails.  Note that the hasher itself buffers any trailing partial block
+    /// of the input until it is finalized.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    #[cfg(feature = "zeroize")]
+    fn update_and_zeroize(&mut self, data: &mut [u8]) -> Result<()> {
+        absorb::and_zeroize(|data| XofHasher::update(self, data), data)
+    }
+    /// Perform a single read of up to `max_bytes` bytes from `reader` and absorb whatever
+    /// was read, returning the number of bytes absorbed.
+    ///
+    /// At most 8 KiB is read per call whatever `max_bytes` is, so `usize::MAX` can be
+    /// passed for "no limit" without allocating it.
+    ///
+    /// Exactly one [`Read::read`] call is made, so partial reads are absorbed as they
+    /// arrive and the caller drives the loop; a return value of `0` (with `max_bytes > 0`)
+    /// signals end of input.  Read errors, including [`io::ErrorKind::WouldBlock`] and
+    /// [`io::ErrorKind::Interrupted`], are returned to the caller without absorbing anything,
+    /// so the call can simply be retried.
+    ///
+    /// # Errors
+    /// An error will be returned if reading from `reader` fails, or if the hasher has already
+    /// been finalized.
+    ///
+    fn update_read_some<R: Read>(&mut self, reader: &mut R, max_bytes: usize) -> io::Result<usize>
+    where
+        Self: Sized,
+    {
+        absorb::read_some(|data| XofHasher::update(self, data), reader, max_bytes)
+    }
+    /// Write all of `data` to `sink` and absorb it, so data can be hashed while it is
+    /// forwarded (e.g. to a file) without a second pass.
+    ///
+    /// `data` is written first, so if the write fails nothing is absorbed and the call can
+    /// be retried, although `sink` may have received part of `data`.
+    ///
+    /// # Errors
+    /// An error will be returned if writing to `sink` fails, or if the hasher has already
+    /// been finalized.
+    ///
+    fn update_tee<W: Write>(&mut self, data: &[u8], sink: &mut W) -> io::Result<()>
+    where
+        Self: Sized,
+    {
+        absorb::tee(|data| XofHasher::update(self, data), data, sink)
+    }
+    /// Read `reader` to the end and absorb everything read, yielding to the runtime at
+    /// every read.
+    ///
+    /// The result is the same as absorbing the same bytes synchronously.  The returned
+    /// future is `Send` so it can be spawned on a multi-threaded runtime.
+    ///
+    /// # Errors
+    /// An error will be returned if reading from `reader` fails, or if the hasher has already
+    /// been finalized.  Bytes read before the error have already been absorbed.
+    ///
+    #[cfg(feature = "tokio")]
+    fn update_async<R>(&mut self, reader: &mut R) -> impl Future<Output = io::Result<()>> + Send
+    where
+        Self: Send + Sized,
+        R: AsyncRead + Unpin + Send,
+    {
+        absorb::read_to_end(|data| XofHasher::update(self, data), reader)
+    }
     /// Finalize the absorbing phase.
     ///
     /// # Errors
     ///
     fn finalize(&mut self) -> Result<()>;
+    /// Finalize the hasher and squeeze a `tag_len` byte tag.
+    ///
+    /// `tag_len` must be at least [`MIN_TAG_BYTES`](crate::MIN_TAG_BYTES) (16 bytes, for
+    /// 128 bits of security), so a dangerously short tag cannot be produced by accident.
+    ///
+    /// # Errors
+    /// An error will be returned if `tag_len` is shorter than the minimum, or if the hasher
+    /// has already been finalized.
+    ///
+    fn finalize_tag(&mut self, tag_len: usize) -> Result<Vec<u8>> {
+        if tag_len < MIN_TAG_BYTES {
+            Err(Sha3Error::TagTooShort(tag_len, MIN_TAG_BYTES).into())
+        } else {
+            let mut tag = vec![0u8; tag_len];
+            self.finalize()?;
+            self.get_bytes(&mut tag, tag_len)?;
+            Ok(tag)
+        }
+    }
     /// Start the squeezing phase and fill the requested number of bytes.
     ///
     /// # Errors
     ///
     fn get_bytes(&mut self, output: &mut [u8], num_bytes: usize) -> Result<()>;
+    /// Squeeze exactly `output.len()` bytes into `output`.
+    ///
+    /// Unlike [`get_bytes`](Self::get_bytes) the number of bytes is taken from `output`, so
+    /// the two lengths cannot disagree.  A finalized XOF can always produce more output, so
+    /// this always fills the whole of `output` once the hasher has been finalized.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying squeeze fails.
+    ///
+    fn get_exact(&mut self, output: &mut [u8]) -> Result<()> {
+        let num_bytes = output.len();
+        self.get_bytes(output, num_bytes)
+    }
+    /// Squeeze `num_bytes` bytes into a new heap buffer aligned to
+    /// [`SIMD_ALIGN_BYTES`](crate::SIMD_ALIGN_BYTES), for consumers that process the output
+    /// with aligned vector loads.
+    ///
+    /// The bytes are exactly those [`get_bytes`](Self::get_bytes) would have produced.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying squeeze fails.
+    ///
+    fn get_bytes_aligned(&mut self, num_bytes: usize) -> Result<AlignedBytes> {
+        let mut output = AlignedBytes::zeroed(num_bytes);
+        self.get_bytes(&mut output, num_bytes)?;
+        Ok(output)
+    }
+    /// Squeeze `num_bytes` bytes and compare them with the next `num_bytes` bytes read from
+    /// `expected`, without buffering either in full.
+    ///
+    /// Output is squeezed and compared in rate sized blocks (as with
+    /// [`verify_long_tag`](Self::verify_long_tag)), and every byte is compared: the
+    /// differences are accumulated and only checked once all `num_bytes` have been
+    /// processed, so the time taken does not reveal where a mismatch occurred.  This makes it
+    /// suitable for verifying long MAC tags.
+    ///
+    /// # Errors
+    /// An error will be returned if squeezing fails, or if `expected` cannot supply
+    /// `num_bytes` bytes.
+    ///
+    fn verify_xof_stream<R: Read>(&mut self, expected: &mut R, num_bytes: usize) -> Result<bool>
+    where
+        Self: Sized,
+    {
+        self.verify_long_tag(expected, num_bytes)
+    }
+    /// Squeeze a `len` byte tag and compare it with the next `len` bytes read from
+    /// `expected`, using memory bounded by the sponge rate however long the tag is.
+    ///
+    /// The tag is squeezed, read, and compared one rate-sized block (at most 168 bytes) at a
+    /// time, and the differences of every block are accumulated and only checked at the
+    /// end, so the time taken does not reveal where a mismatch occurred.  This suits
+    /// extendable-output MACs with tags too long to buffer, e.g. megabytes of output.
+    ///
+    /// # Errors
+    /// An error will be returned if squeezing fails, or if `expected` cannot supply `len`
+    /// bytes.
+    ///
+    fn verify_long_tag<R: Read>(&mut self, expected: &mut R, len: usize) -> Result<bool>
+    where
+        Self: Sized,
+    {
+        let mut squeezed = [0u8; BLOCK_BYTES];
+        let mut tag = [0u8; BLOCK_BYTES];
+        let mut diff = 0u8;
+        let mut remaining = len;
+        while remaining > 0 {
+            let block_len = remaining.min(BLOCK_BYTES);
+            self.get_bytes(&mut squeezed[..block_len], block_len)?;
+            expected.read_exact(&mut tag[..block_len])?;
+            diff = squeezed[..block_len]
+                .iter()
+                .zip(&tag[..block_len])
+                .fold(diff, |acc, (x, y)| acc | (x ^ y));
+            remaining -= block_len;
+        }
+        Ok(diff == 0)
+    }
+    /// Squeeze `CHUNK * COUNT` bytes and split them into `COUNT` arrays of `CHUNK` bytes.
+    ///
+    /// The concatenation of the returned chunks is exactly the output of a single
+    /// `get_bytes` call for `CHUNK * COUNT` bytes, e.g. four 16 byte subkeys can be derived
+    /// as a `[[u8; 16]; 4]`.
+    ///
+    /// # Errors
+    ///
+    fn squeeze_chunks<const CHUNK: usize, const COUNT: usize>(
+        &mut self,
+    ) -> Result<[[u8; CHUNK]; COUNT]>
+    where
+        Self: Sized,
+    {
+        let mut chunks = [[0u8; CHUNK]; COUNT];
+        for chunk in &mut chunks {
+            self.get_bytes(chunk, CHUNK)?;
+        }
+        Ok(chunks)
+    }
+    /// Squeeze one buffer for each entry of `lengths`, in order, from a single contiguous
+    /// squeeze.
+    ///
+    /// The concatenation of the returned buffers is exactly the output of a single
+    /// `get_bytes` call for the sum of `lengths`, so e.g. an encryption key, a MAC key, and
+    /// a nonce of different sizes can be derived with their boundaries made explicit.
+    ///
+    /// # Errors
+    /// An error will be returned if squeezing fails.
+    ///
+    fn squeeze_segments(&mut self, lengths: &[usize]) -> Result<Vec<Vec<u8>>>
+    where
+        Self: Sized,
+    {
+        lengths
+            .iter()
+            .map(|&len| {
+                let mut segment = vec![0u8; len];
+                self.get_bytes(&mut segment, len)?;
+                Ok(segment)
+            })
+            .collect()
+    }
+}
+
+/// Object safe trait for extendable output functions chosen at runtime.
+///
+/// The XOF counterpart to [`DynHasher`], implemented for SHAKE128 and SHAKE256 so either
+/// can be stored as a `Box<dyn DynXof>`.
+pub trait DynXof {
+    /// Update the hasher with new byte data.
+    ///
+    /// # Errors
+    /// An error will be returned if `update` is called after the hasher has been finalized.
+    ///
+    fn update(&mut self, data: &[u8]) -> Result<()>;
+    /// Finalize the absorbing phase.
+    ///
+    /// # Errors
+    /// An error will be returned if `finalize` is called after the hasher has been finalized.
+    ///
+    fn finalize(&mut self) -> Result<()>;
+    /// Squeeze the next `num_bytes` bytes of output.
+    ///
+    /// # Errors
+    ///
+    fn squeeze_to_vec(&mut self, num_bytes: usize) -> Result<Vec<u8>>;
+    /// The security level of the XOF in bits (half the sponge capacity).
+    fn security_bits(&self) -> usize;
 }
 
 /// Trait for hashing data with an arbitrary output size and `BitSlice` input data.
@@ -62,11 +738,112 @@ pub trait XofHasherBits {
     /// An error will be returned if `update_bits` is called after the hasher has been finalized.
     ///
     fn update_bits(&mut self, data: &BitSlice<u8, Lsb0>) -> Result<()>;
+    /// Update the hasher with each chunk of bits produced by `iter`, in order.
+    ///
+    /// Chunks need not be byte-aligned; the result is the same as a single
+    /// [`update_bits`](Self::update_bits) call with the concatenation of every chunk.
+    ///
+    /// # Errors
+    /// An error will be returned if the hasher has already been finalized.
+    ///
+    fn update_bits_iter<'a, I>(&mut self, iter: I) -> Result<()>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = &'a BitSlice<u8, Lsb0>>,
+    {
+        absorb::bits_iter(|bits| XofHasherBits::update_bits(self, bits), iter)
+    }
+    /// Read `total_bits.div_ceil(8)` bytes from `reader` and absorb exactly the first
+    /// `total_bits` of those bits.
+    ///
+    /// Bits are taken least significant bit first from each byte, as with
+    /// [`update_bits`](Self::update_bits), so the unused high bits of the final byte are
+    /// discarded.  This allows hashing a bitstream whose length is not a multiple of 8,
+    /// e.g. a bit-length-prefixed frame read from a socket.  The bytes are read and
+    /// absorbed one block at a time, so memory use does not depend on `total_bits`.
+    ///
+    /// # Errors
+    /// An error will be returned if `reader` ends before all of the bytes have been read,
+    /// if reading from `reader` fails, or if the hasher has already been finalized.  Any
+    /// blocks read before the error have already been absorbed.
+    ///
+    fn update_reader_bits<R: Read>(&mut self, reader: &mut R, total_bits: usize) -> io::Result<()>
+    where
+        Self: Sized,
+    {
+        absorb::reader_bits(
+            |bits| XofHasherBits::update_bits(self, bits),
+            reader,
+            total_bits,
+        )
+    }
+    /// Absorb `full_bytes` followed by the low `trailing_bits` bits of `trailing`.
+    ///
+    /// This hashes a message such as "3 full bytes plus 5 more bits" without building a
+    /// [`BitVec`].  As with [`update_bits`](Self::update_bits), bits are taken least
+    /// significant bit first, so the unused high bits of `trailing` are ignored.
+    ///
+    /// # Errors
+    /// An error will be returned if `trailing_bits` is greater than 8, or if the hasher has
+    /// already been finalized.
+    ///
+    fn update_with_trailing_bits(
+        &mut self,
+        full_bytes: &[u8],
+        trailing: u8,
+        trailing_bits: usize,
+    ) -> Result<()> {
+        absorb::trailing_bits(
+            |bits| XofHasherBits::update_bits(self, bits),
+            full_bytes,
+            trailing,
+            trailing_bits,
+        )
+    }
     /// Start the squeezing phase and fill the requested number of bits.
     ///
     /// # Errors
     ///
     fn get_bits(&mut self, output: &mut BitVec<u8, Lsb0>, num_bits: usize) -> Result<()>;
+    /// Squeeze exactly `num_bits` bits onto the end of `output`.
+    ///
+    /// This is [`get_bits`](Self::get_bits) with an explicit length contract: `output` must
+    /// grow by exactly `num_bits` bits.
+    ///
+    /// # Errors
+    /// An error will be returned if the underlying squeeze fails, or if `output` did not
+    /// grow by exactly `num_bits` bits.
+    ///
+    fn get_bits_exact(&mut self, output: &mut BitVec<u8, Lsb0>, num_bits: usize) -> Result<()> {
+        let before = output.len();
+        self.get_bits(output, num_bits)?;
+        let squeezed = output.len() - before;
+        if squeezed == num_bits {
+            Ok(())
+        } else {
+            Err(Sha3Error::OutputLengthMismatch(squeezed, num_bits).into())
+        }
+    }
+    /// Squeeze `num_bits` bits into bytes, returning the bytes along with the number of
+    /// valid bits in the final byte.
+    ///
+    /// Bits are packed least significant bit first (as with [`get_bits`](Self::get_bits)),
+    /// and any unused high bits of the final byte are zero.  For example, squeezing 20 bits
+    /// returns 3 bytes where only the low 4 bits of the last byte are meaningful.  When
+    /// `num_bits` is a multiple of 8 the final byte is complete and the count is 8.
+    ///
+    /// # Errors
+    ///
+    fn get_partial_byte(&mut self, num_bits: usize) -> Result<(Vec<u8>, u8)> {
+        let mut bits = BitVec::<u8, Lsb0>::with_capacity(num_bits);
+        self.get_bits(&mut bits, num_bits)?;
+        bits.set_uninitialized(false);
+        let valid_bits = match num_bits % 8 {
+            0 if num_bits > 0 => 8,
+            rem => u8::try_from(rem)?,
+        };
+        Ok((bits.into_vec(), valid_bits))
+    }
 }
 
 /// A sponge trait for absorbing and squeezing data (Keccak for example)
@@ -0,0 +1,135 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Fiat-Shamir transcripts built on SHAKE256
+
+use anyhow::Result;
+
+use crate::{Shake256, XofHasher, sp800_185::encoding::left_encode};
+
+/// A Fiat-Shamir transcript of labeled messages, built on SHAKE256.
+///
+/// Every message is absorbed as `left_encode(len(label)) || label || left_encode(len(msg))
+/// || msg`, with both lengths in bytes, so the sequence of `(label, message)` pairs is
+/// recovered unambiguously from the absorbed bytes.  A challenge is squeezed from a copy of
+/// the transcript after absorbing its label and length, and is then appended to the
+/// transcript itself, so every challenge depends on everything before it, including
+/// earlier challenges.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::Transcript;
+/// # pub fn main() -> Result<()> {
+/// let mut prover = Transcript::new(b"example protocol")?;
+/// prover.append(b"commitment", b"...")?;
+/// let challenge = prover.challenge(b"c", 32)?;
+///
+/// let mut verifier = Transcript::new(b"example protocol")?;
+/// verifier.append(b"commitment", b"...")?;
+/// assert_eq!(challenge, verifier.challenge(b"c", 32)?);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Transcript {
+    xof: Shake256,
+}
+
+impl Transcript {
+    /// Create a new transcript for the protocol named by `protocol`.
+    ///
+    /// # Errors
+    /// An error will be returned if the protocol label cannot be absorbed.
+    ///
+    pub fn new(protocol: &[u8]) -> Result<Self> {
+        let mut transcript = Self {
+            xof: Shake256::new(),
+        };
+        transcript.append(b"protocol", protocol)?;
+        Ok(transcript)
+    }
+
+    /// Append the message `msg` to the transcript under `label`.
+    ///
+    /// # Errors
+    /// An error will be returned if the message cannot be absorbed.
+    ///
+    pub fn append(&mut self, label: &[u8], msg: &[u8]) -> Result<()> {
+        self.xof.update(&left_encode(label.len()))?;
+        self.xof.update(label)?;
+        self.xof.update(&left_encode(msg.len()))?;
+        self.xof.update(msg)
+    }
+
+    /// Derive an `out_len` byte challenge under `label` from the transcript so far.
+    ///
+    /// The challenge is appended to the transcript under the same label.
+    ///
+    /// # Errors
+    /// An error will be returned if the challenge cannot be squeezed or appended.
+    ///
+    pub fn challenge(&mut self, label: &[u8], out_len: usize) -> Result<Vec<u8>> {
+        let mut xof = self.xof.clone();
+        xof.update(&left_encode(label.len()))?;
+        xof.update(label)?;
+        xof.update(&left_encode(out_len))?;
+        xof.finalize()?;
+        let mut challenge = vec![0u8; out_len];
+        xof.get_bytes(&mut challenge, out_len)?;
+        self.append(label, &challenge)?;
+        Ok(challenge)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::Transcript;
+
+    fn challenge(messages: &[(&[u8], &[u8])]) -> Result<Vec<u8>> {
+        let mut transcript = Transcript::new(b"test")?;
+        for (label, msg) in messages {
+            transcript.append(label, msg)?;
+        }
+        transcript.challenge(b"challenge", 32)
+    }
+
+    #[test]
+    fn test_challenge_is_deterministic() -> Result<()> {
+        let messages: [(&[u8], &[u8]); 2] = [(b"a", b"first"), (b"b", b"second")];
+        assert_eq!(challenge(&messages)?, challenge(&messages)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_challenge_depends_on_message_order() -> Result<()> {
+        let ordered = challenge(&[(b"a", b"first"), (b"b", b"second")])?;
+        assert_ne!(ordered, challenge(&[(b"a", b"second"), (b"b", b"first")])?);
+        assert_ne!(ordered, challenge(&[(b"b", b"second"), (b"a", b"first")])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_challenge_framing_is_unambiguous() -> Result<()> {
+        // Moving bytes between the label and the message changes the challenge
+        assert_ne!(challenge(&[(b"ab", b"c")])?, challenge(&[(b"a", b"bc")])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_successive_challenges_differ() -> Result<()> {
+        let mut transcript = Transcript::new(b"test")?;
+        transcript.append(b"a", b"first")?;
+        let first = transcript.challenge(b"challenge", 32)?;
+        let second = transcript.challenge(b"challenge", 32)?;
+        assert_ne!(first, second);
+        assert_eq!(16, transcript.challenge(b"short", 16)?.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! A (non-standard) SHA3-256 tree hash for large inputs
+//!
+//! The input is split into `chunk_size` byte chunks and each chunk is hashed as a leaf
+//! with `SHA3-256(0x00 || chunk)`.  Pairs of nodes are then combined level by level with
+//! `SHA3-256(0x01 || left || right)` until a single root remains.  If a level has an odd
+//! number of nodes, the last node is promoted to the next level unchanged.  An empty
+//! input is treated as a single empty chunk.
+//!
+//! This construction is specific to this crate.  It is neither `KangarooTwelve` nor
+//! `ParallelHash` and the root is not equal to the plain SHA3-256 digest of the input.
+
+use anyhow::Result;
+#[cfg(feature = "rayon")]
+use rayon::prelude::{ParallelIterator, ParallelSlice};
+
+use crate::{Hasher, Sha3_256, Sha3Error, constants::SHA3_256_BYTES, utils::ct_eq};
+
+/// The default tree hash chunk size (1 MiB)
+pub const TREE_CHUNK_SIZE: usize = 1024 * 1024;
+
+const LEAF_PREFIX: u8 = 0x00;
+const PARENT_PREFIX: u8 = 0x01;
+
+/// Compute the SHA3-256 tree hash root of `data` using `chunk_size` byte leaves.
+///
+/// With the `rayon` feature enabled the leaves are hashed in parallel.  The root does not
+/// depend on whether the leaves were hashed in parallel or not.
+///
+/// # Errors
+/// An error will be returned if `chunk_size` is zero.
+///
+pub fn sha3_256_tree(data: &[u8], chunk_size: usize) -> Result<[u8; SHA3_256_BYTES]> {
+    let mut level = leaves(data, chunk_size)?;
+
+    while level.len() > 1 {
+        level = next_level(&level)?;
+    }
+    Ok(level[0])
+}
+
+/// Compute the SHA3-256 tree hash root of `data` along with the inclusion proof for the
+/// leaf at `leaf_index`.
+///
+/// The proof is the list of sibling hashes on the path from the leaf to the root, lowest
+/// level first.  Levels where the node is promoted (the last node of an odd level) have
+/// no sibling and contribute nothing to the proof.  The root is the same as
+/// [`sha3_256_tree`] returns.
+///
+/// # Errors
+/// An error will be returned if `chunk_size` is zero or `leaf_index` is not the index of
+/// a leaf.
+///
+pub fn sha3_256_tree_with_proof(
+    data: &[u8],
+    chunk_size: usize,
+    leaf_index: usize,
+) -> Result<([u8; SHA3_256_BYTES], Vec<[u8; SHA3_256_BYTES]>)> {
+    let mut level = leaves(data, chunk_size)?;
+    if leaf_index >= level.len() {
+        return Err(Sha3Error::InvalidLeafIndex(leaf_index, level.len()).into());
+    }
+
+    let mut proof = Vec::new();
+    let mut index = leaf_index;
+    while level.len() > 1 {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        level = next_level(&level)?;
+        index /= 2;
+    }
+    Ok((level[0], proof))
+}
+
+/// Verify that `leaf` (the chunk data, not its hash) is the leaf at `leaf_index` of a tree
+/// with `leaf_count` leaves and the given `root`, using a proof from
+/// [`sha3_256_tree_with_proof`].
+///
+/// The leaf count is needed to know at which levels the node was promoted rather than
+/// combined with a sibling.  For a tree built with chunk size `c` over `n > 0` bytes it is
+/// `n.div_ceil(c)`, and 1 for empty input.
+///
+/// # Errors
+/// An error will be returned if hashing fails.
+///
+pub fn verify_merkle_proof(
+    leaf: &[u8],
+    leaf_index: usize,
+    leaf_count: usize,
+    proof: &[[u8; SHA3_256_BYTES]],
+    root: &[u8; SHA3_256_BYTES],
+) -> Result<bool> {
+    if leaf_index >= leaf_count {
+        return Ok(false);
+    }
+
+    let mut node = leaf_hash(leaf)?;
+    let mut index = leaf_index;
+    let mut count = leaf_count;
+    let mut siblings = proof.iter();
+    while count > 1 {
+        if index ^ 1 < count {
+            let Some(sibling) = siblings.next() else {
+                return Ok(false);
+            };
+            node = if index % 2 == 0 {
+                parent_hash(&node, sibling)?
+            } else {
+                parent_hash(sibling, &node)?
+            };
+        }
+        index /= 2;
+        count = count.div_ceil(2);
+    }
+    Ok(siblings.next().is_none() && ct_eq(&node, root))
+}
+
+fn next_level(level: &[[u8; SHA3_256_BYTES]]) -> Result<Vec<[u8; SHA3_256_BYTES]>> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => parent_hash(left, right),
+            _ => Ok(pair[0]),
+        })
+        .collect()
+}
+
+fn leaves(data: &[u8], chunk_size: usize) -> Result<Vec<[u8; SHA3_256_BYTES]>> {
+    if chunk_size == 0 {
+        Err(Sha3Error::InvalidChunkSize(chunk_size).into())
+    } else if data.is_empty() {
+        Ok(vec![leaf_hash(data)?])
+    } else {
+        #[cfg(feature = "rayon")]
+        {
+            data.par_chunks(chunk_size).map(leaf_hash).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            data.chunks(chunk_size).map(leaf_hash).collect()
+        }
+    }
+}
+
+fn leaf_hash(chunk: &[u8]) -> Result<[u8; SHA3_256_BYTES]> {
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.update(&[LEAF_PREFIX])?;
+    hasher.update(chunk)?;
+    hasher.finalize(&mut result)?;
+    Ok(result)
+}
+
+fn parent_hash(
+    left: &[u8; SHA3_256_BYTES],
+    right: &[u8; SHA3_256_BYTES],
+) -> Result<[u8; SHA3_256_BYTES]> {
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.update(&[PARENT_PREFIX])?;
+    hasher.update(left)?;
+    hasher.update(right)?;
+    hasher.finalize(&mut result)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::{
+        leaf_hash, leaves, parent_hash, sha3_256_tree, sha3_256_tree_with_proof,
+        verify_merkle_proof,
+    };
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..=250).cycle().take(len).collect()
+    }
+
+    #[test]
+    fn test_tree_zero_chunk_size_error() {
+        assert!(sha3_256_tree(b"Hello, world!", 0).is_err());
+    }
+
+    #[test]
+    fn test_tree_single_chunk_is_leaf() -> Result<()> {
+        assert_eq!(leaf_hash(b"")?, sha3_256_tree(b"", 16)?);
+        assert_eq!(
+            leaf_hash(b"Hello, world!")?,
+            sha3_256_tree(b"Hello, world!", 16)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tree_odd_leaf_count() -> Result<()> {
+        let data = data(40);
+        let l0 = leaf_hash(&data[..16])?;
+        let l1 = leaf_hash(&data[16..32])?;
+        let l2 = leaf_hash(&data[32..])?;
+        let expected = parent_hash(&parent_hash(&l0, &l1)?, &l2)?;
+        assert_eq!(expected, sha3_256_tree(&data, 16)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tree_is_deterministic() -> Result<()> {
+        let data = data(2_000);
+        for chunk_size in [7, 64, 136, 1000, 2_000, 4096] {
+            let root = sha3_256_tree(&data, chunk_size)?;
+            assert_eq!(root, sha3_256_tree(&data, chunk_size)?);
+        }
+        assert_ne!(sha3_256_tree(&data, 64)?, sha3_256_tree(&data, 128)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tree_leaves_independent_of_parallelism() -> Result<()> {
+        let data = data(2_000);
+        let sequential = data
+            .chunks(100)
+            .map(leaf_hash)
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(sequential, leaves(&data, 100)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tree_with_proof_verifies() -> Result<()> {
+        let data = data(80);
+        let leaf_count = data.len().div_ceil(16);
+        let root = sha3_256_tree(&data, 16)?;
+
+        for (index, leaf) in data.chunks(16).enumerate() {
+            let (proof_root, proof) = sha3_256_tree_with_proof(&data, 16, index)?;
+            assert_eq!(root, proof_root);
+            assert!(verify_merkle_proof(leaf, index, leaf_count, &proof, &root)?);
+            assert!(!verify_merkle_proof(
+                leaf,
+                index ^ 1,
+                leaf_count,
+                &proof,
+                &root
+            )?);
+        }
+
+        // the promoted fifth leaf only has a sibling at the top level
+        let (_, proof) = sha3_256_tree_with_proof(&data, 16, 4)?;
+        assert_eq!(1, proof.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tree_with_proof_rejects_tampering() -> Result<()> {
+        let data = data(64);
+        let (root, mut proof) = sha3_256_tree_with_proof(&data, 16, 2)?;
+        assert!(verify_merkle_proof(&data[32..48], 2, 4, &proof, &root)?);
+        assert!(!verify_merkle_proof(&data[..16], 2, 4, &proof, &root)?);
+
+        proof[1][0] ^= 0x01;
+        assert!(!verify_merkle_proof(&data[32..48], 2, 4, &proof, &root)?);
+        assert!(!verify_merkle_proof(
+            &data[32..48],
+            2,
+            4,
+            &proof[..1],
+            &root
+        )?);
+        assert!(sha3_256_tree_with_proof(&data, 16, 4).is_err());
+        Ok(())
+    }
+}
@@ -9,7 +9,9 @@
 use std::fmt::Write;
 
 use anyhow::Result;
-use bitvec::{field::BitField, order::Lsb0, vec::BitVec};
+use bitvec::{field::BitField, order::Lsb0, slice::BitSlice, vec::BitVec};
+
+use crate::Sha3Error;
 
 /// bits to hex conversion defined at section B.1 in <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf>
 ///
@@ -18,45 +20,303 @@ use bitvec::{field::BitField, order::Lsb0, vec::BitVec};
 ///
 pub fn b2h(bits: &BitVec<u8, Lsb0>, include_space: bool, upper: bool) -> Result<String> {
     let mut res = String::new();
-    let mut chunks = bits.chunks_exact(8);
-    for byte in &mut chunks {
-        let value: u8 = byte.load_le::<u8>();
+    b2h_into(bits, &mut res, include_space, upper)?;
+    Ok(res)
+}
+
+/// [`b2h`] writing into a reused `String`.
+///
+/// `out` is cleared first but keeps its capacity, so formatting many digests through one
+/// buffer does not allocate a fresh `String` for each.
+///
+/// # Errors
+/// The [`write!`] macro can throw I/O errors.
+///
+pub fn b2h_into(
+    bits: &BitVec<u8, Lsb0>,
+    out: &mut String,
+    include_space: bool,
+    upper: bool,
+) -> Result<()> {
+    out.clear();
+    write_hex(bits, out, include_space.then_some(' '), false, upper)
+}
+
+/// Bits to hex conversion with a configurable separator and prefix.
+///
+/// This generalizes [`b2h`]: the bytes are separated by `sep` (if any) and, if `prefix` is
+/// `true`, preceded by `0x`.  For the same bits `aa 55`:
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{BitVec, Lsb0, b2h_fmt};
+/// # pub fn main() -> Result<()> {
+/// let bits = BitVec::<u8, Lsb0>::from_slice(&[0xaa, 0x55]);
+/// assert_eq!("0xaa55", b2h_fmt(&bits, None, true, false)?);
+/// assert_eq!("aa:55", b2h_fmt(&bits, Some(':'), false, false)?);
+/// assert_eq!("AA 55", b2h_fmt(&bits, Some(' '), false, true)?);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// The [`write!`] macro can throw I/O errors.
+///
+pub fn b2h_fmt(
+    bits: &BitVec<u8, Lsb0>,
+    sep: Option<char>,
+    prefix: bool,
+    upper: bool,
+) -> Result<String> {
+    let mut res = String::new();
+    write_hex(bits, &mut res, sep, prefix, upper)?;
+    Ok(res)
+}
+
+fn write_hex(
+    bits: &BitVec<u8, Lsb0>,
+    out: &mut String,
+    sep: Option<char>,
+    prefix: bool,
+    upper: bool,
+) -> Result<()> {
+    if prefix {
+        out.push_str("0x");
+    }
+    let mut first = true;
+    let mut write_byte = |value: u8| -> Result<()> {
+        if let Some(sep) = sep.filter(|_| !first) {
+            out.push(sep);
+        }
+        first = false;
         if upper {
-            write!(res, "{value:02X}")?;
+            write!(out, "{value:02X}")?;
         } else {
-            write!(res, "{value:02x}")?;
-        }
-        if include_space {
-            res.push(' ');
+            write!(out, "{value:02x}")?;
         }
+        Ok(())
+    };
+    let mut chunks = bits.chunks_exact(8);
+    for byte in &mut chunks {
+        write_byte(byte.load_le::<u8>())?;
+    }
+    let rem = chunks.remainder();
+    if !rem.is_empty() {
+        // the trailing partial byte is padded with zero bits
+        write_byte(rem.load_le::<u8>())?;
     }
+    Ok(())
+}
 
-    let mut rem = chunks.remainder().to_bitvec();
+/// Format bytes in the style of the NIST example values: uppercase hex, with the bytes
+/// separated by single spaces, e.g. `"A7 FF C6"`.
+///
+/// This matches `b2h(&BitVec::from_slice(bytes), true, true)` for byte-aligned data, without
+/// the `BitVec` conversion or the possibility of failure.
+#[must_use]
+pub fn nist_format(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
 
-    if !rem.is_empty() {
-        for _ in 0..8 - rem.len() {
-            rem.push(false);
-        }
-        let value: u8 = rem.load_le::<u8>();
-        if upper {
-            write!(res, "{value:02X}")?;
-        } else {
-            write!(res, "{value:02x}")?;
+/// Strict bits to hex conversion.
+///
+/// Identical to [`b2h`], except that the input must be a whole number of bytes.  [`b2h`]
+/// silently pads a trailing partial byte with zero bits (as the NIST sub-byte test vectors
+/// expect), which can hide a 12-bit input rendered as two bytes.  Use `b2h_strict` where
+/// partial bytes indicate a mistake.
+///
+/// # Errors
+/// * [`Sha3Error::NonByteAligned`] if the length of `bits` is not a multiple of 8.
+/// * The [`write!`] macro can throw I/O errors.
+///
+pub fn b2h_strict(bits: &BitVec<u8, Lsb0>, include_space: bool, upper: bool) -> Result<String> {
+    if bits.len() % 8 == 0 {
+        b2h(bits, include_space, upper)
+    } else {
+        Err(Sha3Error::NonByteAligned(bits.len()).into())
+    }
+}
+
+/// Hex to bits conversion, the inverse of [`b2h`] for byte-aligned data.
+///
+/// Whitespace is ignored, so the spaced output of [`b2h`] and [`nist_format`] is accepted,
+/// and both upper and lower case digits are allowed.  Each pair of digits is one byte, and
+/// the bits of each byte are stored least significant bit first.
+///
+/// # Errors
+/// * [`Sha3Error::InvalidHexDigit`] if `s` contains a character that is not a hex digit.
+/// * [`Sha3Error::OddHexLength`] if `s` does not contain a whole number of bytes.
+///
+pub fn bits_from_hex(s: &str) -> Result<BitVec<u8, Lsb0>> {
+    Ok(BitVec::from_vec(hex_to_bytes(s)?))
+}
+
+pub(crate) fn hex_to_bytes(s: &str) -> Result<Vec<u8>> {
+    let digits = s
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            c.to_digit(16)
+                .and_then(|digit| u8::try_from(digit).ok())
+                .ok_or(Sha3Error::InvalidHexDigit(c))
+        })
+        .collect::<Result<Vec<u8>, Sha3Error>>()?;
+    if digits.len() % 2 == 0 {
+        Ok(digits
+            .chunks_exact(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect())
+    } else {
+        Err(Sha3Error::OddHexLength(digits.len()).into())
+    }
+}
+
+/// Bits to bit string conversion, rendering each bit as `'0'` or `'1'`.
+///
+/// The bits are written in their logical order, first bit first, which is how FIPS 202
+/// and the NIST examples write bit strings, so the 5-bit example message renders as
+/// `11001`.  No separators are added and the length is not padded to a whole byte.
+///
+/// ```
+/// # use shashasha::{Lsb0, b2bits_string, bits};
+/// assert_eq!("11001", b2bits_string(bits![u8, Lsb0; 1, 1, 0, 0, 1]));
+/// // 0x01 is stored least significant bit first, so its first bit is the 1
+/// assert_eq!("10000000", b2bits_string(bits![u8, Lsb0; 1, 0, 0, 0, 0, 0, 0, 0]));
+/// ```
+#[must_use]
+pub fn b2bits_string(bits: &BitSlice<u8, Lsb0>) -> String {
+    bits.iter()
+        .map(|bit| if *bit { '1' } else { '0' })
+        .collect()
+}
+
+/// Bit string to bits conversion, the inverse of [`b2bits_string`].
+///
+/// Whitespace is ignored, so a bit string broken into groups is accepted.  Every other
+/// character must be `'0'` or `'1'`, and the result has exactly as many bits as digits.
+///
+/// # Errors
+/// [`Sha3Error::InvalidBitDigit`] if `s` contains a character that is not `'0'` or `'1'`.
+///
+pub fn bits_from_string(s: &str) -> Result<BitVec<u8, Lsb0>> {
+    s.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '0' => Ok(false),
+            '1' => Ok(true),
+            _ => Err(Sha3Error::InvalidBitDigit(c).into()),
+        })
+        .collect()
+}
+
+/// Fold `input` into `out_len` bytes by xor-ing together its consecutive `out_len` byte
+/// windows.
+///
+/// Output byte `i` is the XOR of input bytes `i`, `i + out_len`, `i + 2 * out_len`, and so
+/// on; a shorter final window is xor-ed into the leading bytes, and an input shorter than
+/// `out_len` is zero padded.  Unlike truncation every input byte affects the result, but
+/// this is a folding primitive, not a standard way to shorten a digest: a folded SHA3-512
+/// digest is not a SHA3-256 digest, and folding does not add security beyond `out_len`
+/// bytes.  An `out_len` of zero gives an empty result.
+///
+/// ```
+/// # use shashasha::fold_digest;
+/// assert_eq!(vec![0x01 ^ 0x03, 0x02 ^ 0x04], fold_digest(&[0x01, 0x02, 0x03, 0x04], 2));
+/// ```
+#[must_use]
+pub fn fold_digest(input: &[u8], out_len: usize) -> Vec<u8> {
+    let mut folded = vec![0u8; out_len];
+    if out_len > 0 {
+        for window in input.chunks(out_len) {
+            for (acc, byte) in folded.iter_mut().zip(window) {
+                *acc ^= byte;
+            }
         }
-        if include_space {
-            res.push(' ');
+    }
+    folded
+}
+
+/// Describe the differences between two digests, for debugging test vector mismatches.
+///
+/// Returns `None` if `a` and `b` are equal.  Otherwise each differing byte is listed on its
+/// own line as `byte {offset}: {a} != {b}` in uppercase hex, and a length mismatch is
+/// reported on a leading line, e.g.
+///
+/// ```
+/// # use shashasha::digest_diff;
+/// assert_eq!(None, digest_diff(&[0xA7, 0xFF], &[0xA7, 0xFF]));
+/// assert_eq!(
+///     Some("length: 3 != 2\nbyte 1: FF != FE".to_string()),
+///     digest_diff(&[0xA7, 0xFF, 0xC6], &[0xA7, 0xFE])
+/// );
+/// ```
+#[must_use]
+pub fn digest_diff(a: &[u8], b: &[u8]) -> Option<String> {
+    if a == b {
+        None
+    } else {
+        let mut lines = Vec::new();
+        if a.len() != b.len() {
+            lines.push(format!("length: {} != {}", a.len(), b.len()));
         }
+        lines.extend(
+            a.iter()
+                .zip(b)
+                .enumerate()
+                .filter(|(_, (x, y))| x != y)
+                .map(|(offset, (x, y))| format!("byte {offset}: {x:02X} != {y:02X}")),
+        );
+        Some(lines.join("\n"))
+    }
+}
+
+/// Compare two byte slices without exiting early on the first difference.
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() == b.len() {
+        a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+    } else {
+        false
     }
-    Ok(res.trim_end().to_string())
+}
+
+/// The LEB128 varint encoding of `v`, as used by protobuf: 7 bits per byte, least
+/// significant group first, with the high bit set on every byte but the last.
+pub(crate) fn varint(mut v: u64) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(10);
+    while v >= 0x80 {
+        encoded.push((v & 0x7f) as u8 | 0x80);
+        v >>= 7;
+    }
+    encoded.push((v & 0x7f) as u8);
+    encoded
+}
+
+/// The 5-bit message `11001` of the NIST SHA-3 and SHAKE examples (`*_Msg5.pdf`).
+#[cfg(test)]
+pub(crate) fn nist_msg_5() -> BitVec<u8, Lsb0> {
+    bitvec::bitvec![u8, Lsb0; 1, 1, 0, 0, 1]
+}
+
+/// The 30-bit message of the NIST SHA-3 and SHAKE examples (`*_Msg30.pdf`).
+#[cfg(test)]
+pub(crate) fn nist_msg_30() -> BitVec<u8, Lsb0> {
+    bitvec::bitvec![u8, Lsb0; 1, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 1, 0, 1, 0, 1, 1, 0, 1, 1, 1, 1, 0, 1, 0, 0, 1, 1, 0]
 }
 
 #[cfg(test)]
 mod test {
-    use super::b2h;
+    use super::{
+        b2bits_string, b2h, b2h_fmt, b2h_into, b2h_strict, bits_from_hex, bits_from_string, ct_eq,
+        digest_diff, fold_digest, nist_format, nist_msg_5, nist_msg_30, varint,
+    };
 
     use anyhow::Result;
 
-    use crate::{Lsb0, bitvec};
+    use crate::{BitVec, Hasher, Lsb0, SHA3_256_BYTES, Sha3_256, bitvec};
 
     #[test]
     fn test_b2h_incude_space_upper() -> Result<()> {
@@ -66,6 +326,34 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_b2h_strict_rejects_partial_bytes() -> Result<()> {
+        let bits = bitvec![u8, Lsb0; 0, 1, 0, 1, 0, 1, 0, 1, 1, 0, 1, 0];
+        assert!(b2h_strict(&bits, true, true).is_err());
+        assert_eq!(b2h(&bits, true, true)?, "AA 05");
+
+        let bits = bitvec![u8, Lsb0; 0, 1, 0, 1, 0, 1, 0, 1, 1, 0, 1, 0, 1, 0, 1, 0];
+        assert_eq!(b2h_strict(&bits, true, true)?, "AA 55");
+        Ok(())
+    }
+
+    #[test]
+    fn test_nist_format() -> Result<()> {
+        let mut hasher = Sha3_256::new();
+        let mut result = [0u8; SHA3_256_BYTES];
+        hasher.finalize(&mut result)?;
+        assert_eq!(
+            "A7 FF C6 F8 BF 1E D7 66 51 C1 47 56 A0 61 D6 62 F5 80 FF 4D E4 3B 49 FA 82 D8 0A 4B 80 F8 43 4A",
+            nist_format(&result)
+        );
+        assert_eq!(
+            b2h(&BitVec::from_slice(&result), true, true)?,
+            nist_format(&result)
+        );
+        assert_eq!("", nist_format(&[]));
+        Ok(())
+    }
+
     #[test]
     fn test_b2h_no_space_upper() -> Result<()> {
         let bits = bitvec![u8, Lsb0; 0, 1, 0, 1, 0, 1, 0, 1, 1, 0, 1, 0, 1, 0, 1, 0];
@@ -89,4 +377,175 @@ mod test {
         assert_eq!(hex, "aa55");
         Ok(())
     }
+
+    #[test]
+    fn test_bits_from_hex() -> Result<()> {
+        let bits = bitvec![u8, Lsb0; 0, 1, 0, 1, 0, 1, 0, 1, 1, 0, 1, 0, 1, 0, 1, 0];
+        assert_eq!(bits, bits_from_hex("aa55")?);
+        assert_eq!(bits, bits_from_hex("AA 55")?);
+        assert_eq!(
+            b2h(&bits_from_hex("0123456789abcdef")?, false, false)?,
+            "0123456789abcdef"
+        );
+        assert!(bits_from_hex("")?.is_empty());
+        assert!(bits_from_hex("aa5").is_err());
+        assert!(bits_from_hex("aa5g").is_err());
+        assert!(bits_from_hex("+a").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_b2h_fmt() -> Result<()> {
+        let bits = bitvec![u8, Lsb0; 0, 1, 0, 1, 0, 1, 0, 1, 1, 0, 1, 0, 1, 0, 1, 0];
+        assert_eq!("0xaa55", b2h_fmt(&bits, None, true, false)?);
+        assert_eq!("aa:55", b2h_fmt(&bits, Some(':'), false, false)?);
+        assert_eq!("AA 55", b2h_fmt(&bits, Some(' '), false, true)?);
+        assert_eq!("0xAA:55", b2h_fmt(&bits, Some(':'), true, true)?);
+        assert_eq!("AA55", b2h_fmt(&bits, None, false, true)?);
+        assert_eq!(
+            b2h(&bits, true, false)?,
+            b2h_fmt(&bits, Some(' '), false, false)?
+        );
+        assert_eq!("0x", b2h_fmt(&BitVec::new(), Some(':'), true, false)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_b2h_into_reuses_buffer() -> Result<()> {
+        let digest = BitVec::<u8, Lsb0>::from_vec((0..8).map(|i| i * 0x1f).collect());
+        let mut out = String::with_capacity(128);
+        let capacity = out.capacity();
+        for len in [64usize, 0, 12, 32] {
+            let bits = digest[..len].to_bitvec();
+            for (include_space, upper) in [(true, true), (false, false)] {
+                b2h_into(&bits, &mut out, include_space, upper)?;
+                assert_eq!(b2h(&bits, include_space, upper)?, out);
+            }
+        }
+        assert_eq!(capacity, out.capacity());
+        Ok(())
+    }
+
+    #[test]
+    fn test_digest_diff() {
+        let digest = [0u8; 32];
+        assert_eq!(None, digest_diff(&digest, &digest));
+
+        let mut changed = digest;
+        changed[17] = 0x5a;
+        let diff = digest_diff(&digest, &changed);
+        assert_eq!(Some("byte 17: 00 != 5A".to_string()), diff);
+
+        let diff = digest_diff(&digest, &changed[..20]).unwrap_or_default();
+        assert!(diff.starts_with("length: 32 != 20\n"));
+        assert!(diff.contains("byte 17"));
+    }
+
+    #[test]
+    fn test_nist_messages() {
+        let msg_5 = nist_msg_5();
+        assert_eq!(5, msg_5.len());
+        assert_eq!(bitvec![u8, Lsb0; 1, 1, 0, 0, 1], msg_5);
+        let msg_30 = nist_msg_30();
+        assert_eq!(30, msg_30.len());
+        // The 30 bits are 0x53 0x58 0x7b and the low 6 bits of 0x19, least significant first
+        assert_eq!(
+            BitVec::<u8, Lsb0>::from_slice(&[0x53, 0x58, 0x7b, 0x19])[..30],
+            msg_30
+        );
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(b"", b""));
+        assert!(ct_eq(b"abc", b"abc"));
+        assert!(!ct_eq(b"abc", b"abd"));
+        assert!(!ct_eq(b"abc", b"ab"));
+    }
+
+    #[test]
+    fn test_varint_continuation_boundaries() {
+        assert_eq!(vec![0x00], varint(0));
+        assert_eq!(vec![0x7f], varint(127));
+        assert_eq!(vec![0x80, 0x01], varint(128));
+        assert_eq!(vec![0xac, 0x02], varint(300));
+        assert_eq!(vec![0xff, 0x7f], varint(16_383));
+        assert_eq!(vec![0x80, 0x80, 0x01], varint(16_384));
+        let mut max = vec![0xff; 9];
+        max.push(0x01);
+        assert_eq!(max, varint(u64::MAX));
+
+        // Decoding gives back the value, and only the last byte lacks the continuation bit
+        for v in [
+            0,
+            1,
+            127,
+            128,
+            255,
+            300,
+            1 << 35,
+            (1 << 56) - 1,
+            1 << 63,
+            u64::MAX,
+        ] {
+            let encoded = varint(v);
+            let decoded = encoded
+                .iter()
+                .rev()
+                .fold(0u64, |acc, byte| (acc << 7) | u64::from(byte & 0x7f));
+            assert_eq!(v, decoded);
+            assert!(
+                encoded[..encoded.len() - 1]
+                    .iter()
+                    .all(|byte| byte & 0x80 != 0)
+            );
+            assert_eq!(0, encoded[encoded.len() - 1] & 0x80);
+        }
+    }
+
+    #[test]
+    fn test_bits_string_round_trip() -> Result<()> {
+        assert_eq!("11001", b2bits_string(&nist_msg_5()));
+        assert_eq!(nist_msg_5(), bits_from_string("11001")?);
+        assert_eq!(
+            "110010100001101011011110100110",
+            b2bits_string(&nist_msg_30())
+        );
+        assert_eq!(
+            nist_msg_30(),
+            bits_from_string("11001 01000 01101 01101 11101 00110")?
+        );
+
+        let bytes = BitVec::<u8, Lsb0>::from_vec(vec![0x01, 0xA5, 0xFF]);
+        let rendered = b2bits_string(&bytes);
+        assert_eq!("100000001010010111111111", rendered);
+        assert_eq!(bytes, bits_from_string(&rendered)?);
+
+        assert_eq!("", b2bits_string(&BitVec::<u8, Lsb0>::new()));
+        assert!(bits_from_string("")?.is_empty());
+        assert!(bits_from_string("0120").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fold_digest() -> Result<()> {
+        let digest = crate::sha3_512(b"Hello, world!")?;
+        let folded = fold_digest(&digest, 32);
+        let halves = digest[..32]
+            .iter()
+            .zip(&digest[32..])
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<u8>>();
+        assert_eq!(halves, folded);
+
+        // A partial final window folds into the leading bytes
+        assert_eq!(
+            vec![1 ^ 4 ^ 7, 2 ^ 5, 3 ^ 6],
+            fold_digest(&[1, 2, 3, 4, 5, 6, 7], 3)
+        );
+        assert_eq!(vec![1, 2, 0], fold_digest(&[1, 2], 3));
+        assert_eq!(digest.to_vec(), fold_digest(&digest, 64));
+        assert!(fold_digest(&digest, 0).is_empty());
+        Ok(())
+    }
 }
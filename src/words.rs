@@ -0,0 +1,101 @@
+// Copyright (c) 2025 shashasha developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Human-readable word fingerprints of digests
+
+// One word per byte value, in byte order.  The words are distinct, and this list must never
+// change, or every fingerprint shown to users would change with it.
+const WORDS: [&str; 256] = [
+    "acid", "acorn", "actor", "adobe", "agent", "alarm", "album", "alert", "alley", "alpha",
+    "angle", "ankle", "apple", "apron", "arena", "armor", "arrow", "aspen", "atlas", "attic",
+    "award", "bacon", "badge", "bagel", "baker", "bamboo", "banjo", "barn", "basil", "basin",
+    "beard", "bench", "berry", "bison", "blade", "blank", "blaze", "bloom", "board", "boat",
+    "boost", "boot", "brain", "brass", "bread", "brick", "bride", "brook", "brush", "bucket",
+    "bugle", "cabin", "cable", "cactus", "camel", "camera", "canal", "candle", "canoe", "canvas",
+    "carpet", "castle", "cedar", "cello", "chalk", "chart", "cheese", "cherry", "chess", "chief",
+    "cider", "cinema", "circle", "citrus", "clamp", "cliff", "clock", "cloud", "clover", "coast",
+    "cocoa", "comet", "coral", "cotton", "cougar", "crane", "crater", "cricket", "crown",
+    "crystal", "cupid", "curtain", "cycle", "dagger", "daisy", "dance", "delta", "denim", "desert",
+    "diary", "dinner", "diver", "dolphin", "domino", "donkey", "dragon", "drum", "eagle", "easel",
+    "echo", "elbow", "ember", "engine", "equator", "falcon", "fence", "ferry", "fiddle", "finch",
+    "flame", "forest", "fossil", "fox", "frost", "galaxy", "garden", "garlic", "gecko", "geyser",
+    "ginger", "glacier", "globe", "goat", "gravel", "guitar", "hammer", "harbor", "harp", "hazel",
+    "helmet", "hickory", "honey", "hornet", "igloo", "island", "ivory", "jacket", "jaguar",
+    "jasmine", "jelly", "jigsaw", "juggler", "jungle", "kayak", "kernel", "kettle", "kiwi",
+    "koala", "ladder", "lagoon", "laser", "lemon", "lentil", "lizard", "llama", "lobster",
+    "locket", "lotus", "lumber", "magnet", "maple", "marble", "meadow", "melon", "meteor",
+    "mirror", "mitten", "monkey", "mosaic", "motor", "mustard", "nectar", "needle", "nickel",
+    "noodle", "nutmeg", "oasis", "ocean", "olive", "onion", "orchid", "otter", "oyster", "paddle",
+    "palace", "panda", "paper", "parrot", "peach", "pebble", "piano", "pickle", "pilot", "pirate",
+    "planet", "plaza", "pocket", "polka", "pony", "poppy", "quartz", "quiver", "rabbit", "radar",
+    "radish", "raven", "ribbon", "rocket", "rodeo", "saddle", "sandal", "satchel", "scarf", "seal",
+    "shovel", "silver", "sketch", "sloth", "spider", "sponge", "stable", "summit", "sunset",
+    "tablet", "tango", "teapot", "tiger", "timber", "toast", "tomato", "tulip", "tundra", "turtle",
+    "umbrella", "unicorn", "valley", "velvet", "violin", "walnut", "walrus", "willow", "window",
+    "wizard", "yacht", "zebra", "zipper",
+];
+
+/// Map the first `word_count` bytes of `digest` to words, for comparing digests by reading
+/// them aloud.
+///
+/// Each byte selects one word from a fixed, embedded list of 256 distinct words, so the
+/// same digest always gives the same phrase, and two phrases of the same length differ
+/// whenever the leading bytes differ.  At most `digest.len()` words are returned.  Each
+/// word carries 8 bits of the digest, so e.g. 8 words compare 64 bits.
+///
+/// ```
+/// # use anyhow::Result;
+/// # use shashasha::{fingerprint_words, sha3_256};
+/// # pub fn main() -> Result<()> {
+/// let words = fingerprint_words(&sha3_256(b"my public key")?, 6);
+/// assert_eq!(6, words.len());
+/// println!("Read this aloud: {}", words.join(" "));
+/// #     Ok(())
+/// # }
+/// ```
+#[must_use]
+pub fn fingerprint_words(digest: &[u8], word_count: usize) -> Vec<String> {
+    digest
+        .iter()
+        .take(word_count)
+        .map(|&byte| WORDS[usize::from(byte)].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use anyhow::Result;
+
+    use super::{WORDS, fingerprint_words};
+    use crate::sha3_256;
+
+    #[test]
+    fn test_wordlist_is_distinct() {
+        assert_eq!(256, WORDS.iter().collect::<HashSet<_>>().len());
+    }
+
+    #[test]
+    fn test_fingerprint_words_are_stable() -> Result<()> {
+        // The SHA3-256 digest of "" starts A7 FF C6 F8
+        let digest = sha3_256(b"")?;
+        assert_eq!(
+            vec!["lotus", "zipper", "peach", "walnut"],
+            fingerprint_words(&digest, 4)
+        );
+        assert_eq!(
+            vec!["acid", "acorn", "zipper"],
+            fingerprint_words(&[0x00, 0x01, 0xff], 3)
+        );
+        assert_eq!(fingerprint_words(&digest, 8), fingerprint_words(&digest, 8));
+        assert_eq!(32, fingerprint_words(&digest, 100).len());
+        assert!(fingerprint_words(&digest, 0).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "tokio")]
+
+use std::io::Cursor;
+
+use anyhow::Result;
+use shashasha::{
+    Hasher, SHA3_256_BYTES, Sha3_256, Sha3Writer, Shake256, XofHasher, nist_format, sha3_256,
+    shake256,
+};
+
+#[tokio::test]
+async fn sha256_update_async() -> Result<()> {
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher
+        .update_async(&mut Cursor::new(b"Hello, world!"))
+        .await?;
+    hasher.finalize(&mut result)?;
+    assert_eq!(
+        "F3 45 A2 19 DA 00 5E BE 9C 1A 1E AA D9 7B BF 38 A1 0C 84 73 E4 1D 0A F7 FB 61 7C AA 0C 6A A7 22",
+        nist_format(&result)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn shake256_update_async_matches_sync() -> Result<()> {
+    // Larger than the read buffer, so several reads are absorbed
+    let data = (0..=255).cycle().take(20_000).collect::<Vec<u8>>();
+    let mut hasher = Shake256::new();
+    hasher.update_async(&mut Cursor::new(&data)).await?;
+    hasher.finalize()?;
+    assert_eq!(
+        shake256(&data, 64)?,
+        hasher.by_ref().take(64).collect::<Vec<u8>>()
+    );
+    assert!(
+        hasher
+            .update_async(&mut Cursor::new(b"too late"))
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn sha3_writer_copy() -> Result<()> {
+    let data = (0..=255).cycle().take(20_000).collect::<Vec<u8>>();
+    let mut writer = Sha3Writer::new();
+    let copied = tokio::io::copy(&mut Cursor::new(&data), &mut writer).await?;
+    assert_eq!(20_000, copied);
+    assert_eq!(sha3_256(&data)?, writer.finalize()?);
+    Ok(())
+}
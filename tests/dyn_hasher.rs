@@ -0,0 +1,32 @@
+use anyhow::Result;
+use shashasha::{DynHasher, Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+
+#[test]
+fn dyn_hasher_boxed_variants() -> Result<()> {
+    let hashers: Vec<Box<dyn DynHasher>> = vec![
+        Box::new(Sha3_224::new()),
+        Box::new(Sha3_256::new()),
+        Box::new(Sha3_384::new()),
+        Box::new(Sha3_512::new()),
+    ];
+    let expected = [
+        "6a33e22f20f16642697e8bd549ff7b759252ad56c05a1b0acc31dc69",
+        "f345a219da005ebe9c1a1eaad97bbf38a10c8473e41d0af7fb617caa0c6aa722",
+        "6ba9ea268965916f5937228dde678c202f9fe756a87d8b1b7362869583a45901fd1a27289d72fc0e3ff48b1b78827d3a",
+        "8e47f1185ffd014d238fabd02a1a32defe698cbf38c037a90e3c0a0a32370fb52cbd641250508502295fcabcbf676c09470b27443868c8e5f70e26dc337288af",
+    ];
+
+    for (mut hasher, expected) in hashers.into_iter().zip(expected) {
+        hasher.update(b"Hello, ")?;
+        hasher.update(b"world!")?;
+        let digest = hasher.finalize_to_vec()?;
+        assert_eq!(hasher.output_len(), digest.len());
+        let hex = digest
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+        assert_eq!(expected, hex);
+        assert!(hasher.finalize_to_vec().is_err());
+    }
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anyhow::Result;
+use shashasha::{Shake128, Shake256, XofHasher, shake_for_security};
+
+#[test]
+fn dyn_xof_shake_for_security() -> Result<()> {
+    for (bits, security_bits) in [(112, 128), (128, 128), (192, 256), (256, 256)] {
+        let mut xof = shake_for_security(bits)?;
+        assert_eq!(security_bits, xof.security_bits());
+        xof.update(b"Hello, world!")?;
+        xof.finalize()?;
+        let output = xof.squeeze_to_vec(32)?;
+
+        let mut expected = [0u8; 32];
+        if security_bits == 128 {
+            let mut hasher = Shake128::new();
+            hasher.update(b"Hello, world!")?;
+            hasher.finalize()?;
+            hasher.get_bytes(&mut expected, 32)?;
+        } else {
+            let mut hasher = Shake256::new();
+            hasher.update(b"Hello, world!")?;
+            hasher.finalize()?;
+            hasher.get_bytes(&mut expected, 32)?;
+        }
+        assert_eq!(expected.as_slice(), output);
+    }
+    Ok(())
+}
+
+#[test]
+fn dyn_xof_unsupported_security_level() {
+    assert!(shake_for_security(257).is_err());
+    assert!(shake_for_security(512).is_err());
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use shashasha::{Hasher, Sha3_256, Shake128, XofHasher, nist_format};
+
+#[test]
+fn base_hashing_is_always_available() -> Result<()> {
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; 32];
+    hasher.update(b"")?;
+    hasher.finalize(&mut result)?;
+    assert_eq!(
+        "A7 FF C6 F8 BF 1E D7 66 51 C1 47 56 A0 61 D6 62 F5 80 FF 4D E4 3B 49 FA 82 D8 0A 4B 80 F8 43 4A",
+        nist_format(&result)
+    );
+
+    let mut hasher = Shake128::new();
+    hasher.finalize()?;
+    assert_eq!(
+        "7F 9C 2B A4",
+        nist_format(&hasher.take(4).collect::<Vec<u8>>())
+    );
+    Ok(())
+}
+
+#[cfg(feature = "sp800-185")]
+#[test]
+fn sp800_185_functions_with_feature() -> Result<()> {
+    use shashasha::{CShake128, derive_named};
+
+    let mut hasher = CShake128::new(b"", b"Email Signature")?;
+    let mut result = [0u8; 32];
+    hasher.update(&[0x00, 0x01, 0x02, 0x03])?;
+    hasher.finalize()?;
+    hasher.get_bytes(&mut result, 32)?;
+    assert_eq!(
+        "C1 C3 69 25 B6 40 9A 04 F1 B5 04 FC BC A9 D8 2B 40 17 27 7C B5 ED 2B 20 65 FC 1D 38 14 D5 AA F5",
+        nist_format(&result)
+    );
+
+    let keys = derive_named(b"secret", &[("enc", 32), ("mac", 16)])?;
+    assert_eq!(32, keys["enc"].len());
+    assert_eq!(16, keys["mac"].len());
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use anyhow::Result;
+use shashasha::{Hasher, Sha3_256, Sha3Scratch};
+
+// Counts the allocations made on the current thread, so concurrently running tests do not
+// affect the count.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+fn item(i: usize) -> [u8; 8] {
+    (i as u64).to_le_bytes()
+}
+
+fn hash_with_scratch(scratch: &mut Sha3Scratch, data: &[u8]) -> Result<[u8; 32]> {
+    let mut digest = [0u8; 32];
+    let mut hasher = Sha3_256::with_scratch(scratch);
+    hasher.update(data)?;
+    hasher.finalize(&mut digest)?;
+    Ok(digest)
+}
+
+#[test]
+fn scratch_hashing_does_not_allocate() -> Result<()> {
+    const ITEMS: usize = 500;
+
+    let mut scratch = Sha3Scratch::new();
+    let mut digests = vec![[0u8; 32]; ITEMS];
+    // The first hash grows the scratch buffers to their working size
+    let _ = hash_with_scratch(&mut scratch, &item(0))?;
+
+    let before = allocations();
+    for (i, digest) in digests.iter_mut().enumerate() {
+        *digest = hash_with_scratch(&mut scratch, &item(i))?;
+    }
+    assert_eq!(0, allocations() - before);
+
+    // The standard hashers allocate fresh buffers for every hash
+    let before = allocations();
+    for (i, expected) in digests.iter().enumerate() {
+        let mut digest = [0u8; 32];
+        let mut hasher = Sha3_256::new();
+        hasher.update(&item(i))?;
+        hasher.finalize(&mut digest)?;
+        assert_eq!(expected, &digest);
+    }
+    assert!(allocations() - before >= ITEMS);
+    Ok(())
+}
+
+#[test]
+fn scratch_update_repeated_does_not_allocate() -> Result<()> {
+    let mut scratch = Sha3Scratch::new();
+    // Grow the message buffer to hold a partial block plus a whole repeated block
+    let _ = hash_with_scratch(&mut scratch, &[0xAB; 400])?;
+
+    let mut result = [0u8; 32];
+    let before = allocations();
+    let mut hasher = Sha3_256::with_scratch(&mut scratch);
+    hasher.update_repeated(0xAB, 100_000)?;
+    hasher.finalize(&mut result)?;
+    drop(hasher);
+    assert_eq!(0, allocations() - before);
+
+    let mut expected = [0u8; 32];
+    let mut hasher = Sha3_256::new();
+    hasher.update(&vec![0xAB; 100_000])?;
+    hasher.finalize(&mut expected)?;
+    assert_eq!(expected, result);
+    Ok(())
+}
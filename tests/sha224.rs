@@ -30,3 +30,46 @@ fn sha224_with_update_bits() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn sha224_digest_bits_matches_finalize() -> Result<()> {
+    let mut hasher = Sha3_224::new();
+    let mut result = [0u8; SHA3_224_BYTES];
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize(&mut result)?;
+
+    let mut hasher = Sha3_224::new();
+    hasher.update(b"Hello, world!")?;
+    let bits = hasher.digest_bits()?;
+    assert_eq!(SHA3_224_BYTES * 8, bits.len());
+    assert_eq!(BitVec::<u8, Lsb0>::from_slice(&result), bits);
+    assert_eq!(result.as_slice(), bits.as_raw_slice());
+    assert!(hasher.digest_bits().is_err());
+    Ok(())
+}
+
+#[test]
+fn sha224_single_large_update_bits() -> Result<()> {
+    // 3003 bits spans several rate blocks and ends with a partial byte
+    let data = (0..=250).cycle().take(376).collect::<Vec<u8>>();
+    let bits = &BitVec::<u8, Lsb0>::from_slice(&data)[..3003];
+
+    let mut hasher = Sha3_224::new();
+    let mut single = [0u8; SHA3_224_BYTES];
+    hasher.update_bits(bits)?;
+    hasher.finalize(&mut single)?;
+    let res = b2h(&BitVec::<u8, Lsb0>::from_slice(&single), false, false)?;
+    assert_eq!(
+        "79127808c85b9de60a44ac4b74a87efbb1e925a660cfecd1e224bc06",
+        res
+    );
+
+    let mut hasher = Sha3_224::new();
+    let mut pieces = [0u8; SHA3_224_BYTES];
+    for piece in bits.chunks(13) {
+        hasher.update_bits(piece)?;
+    }
+    hasher.finalize(&mut pieces)?;
+    assert_eq!(single, pieces);
+    Ok(())
+}
@@ -1,5 +1,7 @@
+use std::io::IoSlice;
+
 use anyhow::Result;
-use shashasha::{BitVec, Hasher, HasherBits, Lsb0, SHA3_256_BYTES, Sha3_256, b2h, bits};
+use shashasha::{BitVec, Hasher, HasherBits, Lsb0, SHA3_256_BYTES, Sha3_256, b2h, bits, sha3_256};
 
 #[test]
 fn sha256_with_update() -> Result<()> {
@@ -30,3 +32,344 @@ fn sha256_with_update_bits() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn sha256_digest_bits_matches_finalize() -> Result<()> {
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize(&mut result)?;
+
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"Hello, world!")?;
+    let bits = hasher.digest_bits()?;
+    assert_eq!(SHA3_256_BYTES * 8, bits.len());
+    assert_eq!(BitVec::<u8, Lsb0>::from_slice(&result), bits);
+    assert_eq!(result.as_slice(), bits.as_raw_slice());
+    assert!(hasher.digest_bits().is_err());
+    Ok(())
+}
+
+#[test]
+fn sha256_optimal_update_size() {
+    let hasher = Sha3_256::new();
+    assert_eq!(136, hasher.optimal_update_size());
+}
+
+#[test]
+fn sha256_padding_block_boundaries() -> Result<()> {
+    // message lengths (in bits) around the 1088 bit rate, including the 2 suffix bits
+    for (num_bits, expected) in [
+        (
+            1085,
+            "9a2def078521b72e38a9a7a4f5d70ee1170f7b95c1a40de75eb5616d3feaac9a",
+        ),
+        (
+            1086,
+            "0251e103581e85d235009b4fbbfc40f6495c27bbbb362fcad905fba77c63e657",
+        ),
+        (
+            1087,
+            "3a650b9e42bc8204b09d22c1683a00a283377961fddb2562fb06ef64aefc3a6c",
+        ),
+        (
+            1088,
+            "1cb46a10dd3ee351917ec48d45d84a245587ebce91f33eb07372d95809a278b8",
+        ),
+        (
+            2174,
+            "31adf3f8f0b2776f200b6e39c92fd71950766797bcab9662b1d8797da27727d1",
+        ),
+    ] {
+        let mut hasher = Sha3_256::new();
+        let mut result = [0u8; SHA3_256_BYTES];
+        hasher.update_bits(&BitVec::<u8, Lsb0>::repeat(true, num_bits))?;
+        hasher.finalize(&mut result)?;
+        let res = b2h(&BitVec::<u8, Lsb0>::from_slice(&result), false, false)?;
+        assert_eq!(expected, res, "{num_bits} bits");
+    }
+    Ok(())
+}
+
+#[test]
+fn sha256_rate_aligned_updates() -> Result<()> {
+    let data = (0..=250).cycle().take(3 * 136 + 7).collect::<Vec<u8>>();
+
+    let mut hasher = Sha3_256::new();
+    let mut expected = [0u8; SHA3_256_BYTES];
+    hasher.update(&data)?;
+    hasher.finalize(&mut expected)?;
+
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    for chunk in data.chunks(hasher.optimal_update_size()) {
+        hasher.update(chunk)?;
+    }
+    hasher.finalize(&mut result)?;
+    assert_eq!(expected, result);
+    Ok(())
+}
+
+#[test]
+fn sha256_update_fixed_width_integers() -> Result<()> {
+    let mut hasher = Sha3_256::new();
+    let mut expected = [0u8; SHA3_256_BYTES];
+    hasher.update(&0x0102_0304_0506_0708u64.to_le_bytes())?;
+    hasher.update(&0x0102_0304_0506_0708u64.to_be_bytes())?;
+    hasher.update(&0x0A0B_0C0Du32.to_le_bytes())?;
+    hasher.update(&0x0A0B_0C0Du32.to_be_bytes())?;
+    hasher.finalize(&mut expected)?;
+
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.update_u64_le(0x0102_0304_0506_0708)?;
+    hasher.update_u64_be(0x0102_0304_0506_0708)?;
+    hasher.update_u32_le(0x0A0B_0C0D)?;
+    hasher.update_u32_be(0x0A0B_0C0D)?;
+    hasher.finalize(&mut result)?;
+    assert_eq!(expected, result);
+    Ok(())
+}
+
+#[test]
+fn sha256_update_varint() -> Result<()> {
+    let mut hasher = Sha3_256::new();
+    let mut expected = [0u8; SHA3_256_BYTES];
+    hasher.update(&[0xAC, 0x02, 0x7F, 0x80, 0x01, 0x00])?;
+    hasher.finalize(&mut expected)?;
+
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    for value in [300, 127, 128, 0] {
+        hasher.update_varint(value)?;
+    }
+    hasher.finalize(&mut result)?;
+    assert_eq!(expected, result);
+    assert!(hasher.update_varint(1).is_err());
+    Ok(())
+}
+
+#[test]
+fn sha256_update_tee() -> Result<()> {
+    let data = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+    let mut hasher = Sha3_256::new();
+    let mut sink = Vec::new();
+    for chunk in data.chunks(300) {
+        hasher.update_tee(chunk, &mut sink)?;
+    }
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.finalize(&mut result)?;
+    assert_eq!(data, sink);
+    assert_eq!(sha3_256(&data)?, result);
+
+    // A failed write absorbs nothing
+    let mut hasher = Sha3_256::new();
+    let mut small = [0u8; 10];
+    assert!(hasher.update_tee(&data, &mut small.as_mut_slice()).is_err());
+    hasher.update_tee(b"Hello, world!", &mut Vec::new())?;
+    hasher.finalize(&mut result)?;
+    assert_eq!(sha3_256(b"Hello, world!")?, result);
+    Ok(())
+}
+
+#[test]
+fn sha256_finalize_equals_hex() -> Result<()> {
+    let empty = "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a";
+    let forms = [
+        empty.to_string(),
+        empty.to_uppercase(),
+        "A7 FF C6 F8 BF 1E D7 66 51 C1 47 56 A0 61 D6 62\n\
+         F5 80 FF 4D E4 3B 49 FA 82 D8 0A 4B 80 F8 43 4A"
+            .to_string(),
+    ];
+    for form in forms {
+        assert!(Sha3_256::new().finalize_equals_hex(&form)?);
+    }
+
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"Hello, world!")?;
+    assert!(!hasher.finalize_equals_hex(empty)?);
+    assert!(!Sha3_256::new().finalize_equals_hex(&empty[..62])?);
+
+    // Invalid hex is an error and leaves the hasher usable
+    let mut hasher = Sha3_256::new();
+    assert!(hasher.finalize_equals_hex("not hex").is_err());
+    assert!(hasher.finalize_equals_hex(empty)?);
+    assert!(hasher.finalize_equals_hex(empty).is_err());
+    Ok(())
+}
+
+#[test]
+fn sha256_update_matrix() -> Result<()> {
+    fn matrix_digest(rows: &[&[u8]]) -> Result<[u8; SHA3_256_BYTES]> {
+        let mut hasher = Sha3_256::new();
+        let mut result = [0u8; SHA3_256_BYTES];
+        hasher.update_matrix(rows)?;
+        hasher.finalize(&mut result)?;
+        Ok(result)
+    }
+
+    let two_by_three: [&[u8]; 2] = [&[1, 2, 3], &[4, 5, 6]];
+    let three_by_two: [&[u8]; 3] = [&[1, 2], &[3, 4], &[5, 6]];
+    assert_ne!(matrix_digest(&two_by_three)?, matrix_digest(&three_by_two)?);
+    assert_eq!(
+        matrix_digest(&two_by_three)?,
+        matrix_digest(&[&[1, 2, 3], &[4, 5, 6]])?
+    );
+    assert_ne!(matrix_digest(&[])?, matrix_digest(&[&[]])?);
+
+    // 2 rows, then a 3 byte row and another 3 byte row, each length left encoded
+    assert_eq!(
+        sha3_256(&[1, 2, 1, 3, 1, 2, 3, 1, 3, 4, 5, 6])?,
+        matrix_digest(&two_by_three)?
+    );
+    Ok(())
+}
+
+#[test]
+fn sha256_single_large_update_bits() -> Result<()> {
+    // 3003 bits spans several rate blocks and ends with a partial byte
+    let data = (0..=250).cycle().take(376).collect::<Vec<u8>>();
+    let bits = &BitVec::<u8, Lsb0>::from_slice(&data)[..3003];
+
+    let mut hasher = Sha3_256::new();
+    let mut single = [0u8; SHA3_256_BYTES];
+    hasher.update_bits(bits)?;
+    hasher.finalize(&mut single)?;
+    let res = b2h(&BitVec::<u8, Lsb0>::from_slice(&single), false, false)?;
+    assert_eq!(
+        "7d998e7e9791a68afe06c16df686b187aa1bd704a3aeb9c519ee6ddfaa509d06",
+        res
+    );
+
+    let mut hasher = Sha3_256::new();
+    let mut pieces = [0u8; SHA3_256_BYTES];
+    for piece in bits.chunks(13) {
+        hasher.update_bits(piece)?;
+    }
+    hasher.finalize(&mut pieces)?;
+    assert_eq!(single, pieces);
+    Ok(())
+}
+
+#[test]
+fn sha256_update_bits_iter() -> Result<()> {
+    // The 30-bit NIST message split into three ragged chunks
+    let message = bits![u8, Lsb0; 1, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 1, 0, 1, 0, 1, 1, 0, 1, 1, 1, 1, 0, 1, 0, 0, 1, 1, 0];
+    let chunks = [&message[..3], &message[3..17], &message[17..]];
+
+    let mut hasher = Sha3_256::new();
+    let mut expected = [0u8; SHA3_256_BYTES];
+    hasher.update_bits(message)?;
+    hasher.finalize(&mut expected)?;
+
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.update_bits_iter(chunks)?;
+    hasher.finalize(&mut result)?;
+    assert_eq!(expected, result);
+    let res = b2h(&BitVec::<u8, Lsb0>::from_slice(&result), false, false)?;
+    assert_eq!(
+        "c8242fef409e5ae9d1f1c857ae4dc624b92b19809f62aa8c07411c54a078b1d0",
+        res
+    );
+    Ok(())
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn sha256_update_and_zeroize() -> Result<()> {
+    let mut secret = *b"correct horse battery staple";
+
+    let mut hasher = Sha3_256::new();
+    let mut expected = [0u8; SHA3_256_BYTES];
+    hasher.update(&secret)?;
+    hasher.finalize(&mut expected)?;
+
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.update_and_zeroize(&mut secret)?;
+    assert!(secret.iter().all(|b| *b == 0));
+    hasher.finalize(&mut result)?;
+    assert_eq!(expected, result);
+    Ok(())
+}
+
+#[test]
+fn sha256_update_io_slices() -> Result<()> {
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.update_io_slices(&[IoSlice::new(b"Hello, "), IoSlice::new(b"world!")])?;
+    hasher.finalize(&mut result)?;
+    let res = b2h(&BitVec::<u8, Lsb0>::from_slice(&result), false, false)?;
+    assert_eq!(
+        "f345a219da005ebe9c1a1eaad97bbf38a10c8473e41d0af7fb617caa0c6aa722",
+        res
+    );
+    Ok(())
+}
+
+#[test]
+fn sha256_update_repeated() -> Result<()> {
+    let mut hasher = Sha3_256::new();
+    let mut expected = [0u8; SHA3_256_BYTES];
+    hasher.update(&vec![0xAB; 100_000])?;
+    hasher.finalize(&mut expected)?;
+
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.update_repeated(0xAB, 100_000)?;
+    hasher.finalize(&mut result)?;
+    assert_eq!(expected, result);
+    Ok(())
+}
+
+#[test]
+fn sha256_finalize_tag_minimum_length() -> Result<()> {
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"Hello, world!")?;
+    assert!(hasher.finalize_tag(8).is_err());
+    assert!(hasher.finalize_tag(33).is_err());
+    let tag = hasher.finalize_tag(16)?;
+    let res = b2h(&BitVec::<u8, Lsb0>::from_slice(&tag), false, false)?;
+    assert_eq!("f345a219da005ebe9c1a1eaad97bbf38", res);
+    assert!(hasher.finalize_tag(16).is_err());
+    Ok(())
+}
+
+#[test]
+fn sha256_update_with_trailing_bits() -> Result<()> {
+    // The 30-bit NIST message is 3 full bytes plus the low 6 bits of 0x19
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.update_with_trailing_bits(&[0x53, 0x58, 0x7b], 0x19, 6)?;
+    hasher.finalize(&mut result)?;
+    let res = b2h(&BitVec::<u8, Lsb0>::from_slice(&result), false, false)?;
+    assert_eq!(
+        "c8242fef409e5ae9d1f1c857ae4dc624b92b19809f62aa8c07411c54a078b1d0",
+        res
+    );
+
+    let mut hasher = Sha3_256::new();
+    assert!(hasher.update_with_trailing_bits(&[0x53], 0x19, 9).is_err());
+    Ok(())
+}
+
+#[test]
+fn sha256_update_hex() -> Result<()> {
+    let mut hasher = Sha3_256::new();
+    let mut expected = [0u8; SHA3_256_BYTES];
+    hasher.update(b"Hello")?;
+    hasher.finalize(&mut expected)?;
+
+    let mut hasher = Sha3_256::new();
+    let mut result = [0u8; SHA3_256_BYTES];
+    hasher.update_hex("48656c6c6f")?;
+    hasher.finalize(&mut result)?;
+    assert_eq!(expected, result);
+
+    let mut hasher = Sha3_256::new();
+    assert!(hasher.update_hex("48656c6c6").is_err());
+    assert!(hasher.update_hex("zz").is_err());
+    Ok(())
+}
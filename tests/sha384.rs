@@ -30,3 +30,20 @@ fn sha384_with_update_bits() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn sha384_digest_bits_matches_finalize() -> Result<()> {
+    let mut hasher = Sha3_384::new();
+    let mut result = [0u8; SHA3_384_BYTES];
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize(&mut result)?;
+
+    let mut hasher = Sha3_384::new();
+    hasher.update(b"Hello, world!")?;
+    let bits = hasher.digest_bits()?;
+    assert_eq!(SHA3_384_BYTES * 8, bits.len());
+    assert_eq!(BitVec::<u8, Lsb0>::from_slice(&result), bits);
+    assert_eq!(result.as_slice(), bits.as_raw_slice());
+    assert!(hasher.digest_bits().is_err());
+    Ok(())
+}
@@ -1,5 +1,9 @@
+use std::io::{Cursor, IoSlice};
+
 use anyhow::Result;
-use shashasha::{BitVec, Lsb0, SHA3_512_BYTES, Shake128, XofHasher, XofHasherBits, b2h, bits};
+use shashasha::{
+    BitVec, Lsb0, SHA3_512_BYTES, SIMD_ALIGN_BYTES, Shake128, XofHasher, XofHasherBits, b2h, bits,
+};
 
 #[test]
 fn shake128_with_update() -> Result<()> {
@@ -63,3 +67,291 @@ fn shake128_with_update_iter_implicit_finalize() -> Result<()> {
     assert_eq!(Some(0x12), next);
     Ok(())
 }
+
+#[test]
+fn shake128_get_partial_byte() -> Result<()> {
+    let mut hasher = Shake128::new();
+    hasher.finalize()?;
+    let (bytes, valid_bits) = hasher.get_partial_byte(20)?;
+    assert_eq!(vec![0x7F, 0x9C, 0x0B], bytes);
+    assert_eq!(4, valid_bits);
+
+    let mut hasher = Shake128::new();
+    hasher.finalize()?;
+    let (bytes, valid_bits) = hasher.get_partial_byte(24)?;
+    assert_eq!(vec![0x7F, 0x9C, 0x2B], bytes);
+    assert_eq!(8, valid_bits);
+
+    let (bytes, valid_bits) = hasher.get_partial_byte(0)?;
+    assert!(bytes.is_empty());
+    assert_eq!(0, valid_bits);
+    Ok(())
+}
+
+#[test]
+fn shake128_squeeze_chunks() -> Result<()> {
+    let mut hasher = Shake128::new();
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize()?;
+    let chunks = hasher.squeeze_chunks::<16, 4>()?;
+
+    let mut hasher = Shake128::new();
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize()?;
+    let mut flat = [0u8; 64];
+    hasher.get_bytes(&mut flat, 64)?;
+
+    assert_eq!(flat.as_slice(), chunks.as_flattened());
+    Ok(())
+}
+
+#[test]
+fn shake128_squeeze_segments() -> Result<()> {
+    let mut hasher = Shake128::new();
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize()?;
+    let segments = hasher.squeeze_segments(&[16, 32, 8])?;
+    assert_eq!(
+        vec![16, 32, 8],
+        segments.iter().map(Vec::len).collect::<Vec<usize>>()
+    );
+
+    let mut hasher = Shake128::new();
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize()?;
+    let mut flat = [0u8; 56];
+    hasher.get_bytes(&mut flat, 56)?;
+
+    assert_eq!(flat.as_slice(), segments.concat());
+    Ok(())
+}
+
+#[test]
+fn shake128_optimal_update_size() {
+    let hasher = Shake128::new();
+    assert_eq!(168, hasher.optimal_update_size());
+}
+
+#[test]
+fn shake128_padding_block_boundaries() -> Result<()> {
+    // with the 4 suffix bits these fill the 1344 bit rate exactly, and one bit past it
+    for (num_bits, expected) in [
+        (
+            1340,
+            "68d31a0ca8902dfcb793d500f5c7dcd7c597a41ecfaf6bef8a9956824f7f1ad2",
+        ),
+        (
+            1341,
+            "bcc8bd1ebbe05f1d4e0448c8927837813e2a0c05cbae34fb5501ff221cdae887",
+        ),
+    ] {
+        let mut hasher = Shake128::new();
+        let mut result = [0u8; 32];
+        hasher.update_bits(&BitVec::<u8, Lsb0>::repeat(true, num_bits))?;
+        hasher.finalize()?;
+        hasher.get_bytes(&mut result, 32)?;
+        let res = b2h(&BitVec::<u8, Lsb0>::from_slice(&result), false, false)?;
+        assert_eq!(expected, res, "{num_bits} bits");
+    }
+    Ok(())
+}
+
+#[test]
+fn shake128_get_bytes_aligned() -> Result<()> {
+    let mut hasher = Shake128::new();
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize()?;
+    let mut expected = Shake128::new();
+    expected.update(b"Hello, world!")?;
+    expected.finalize()?;
+
+    for num_bytes in [0, 1, 31, 168, 500] {
+        let aligned = hasher.get_bytes_aligned(num_bytes)?;
+        assert_eq!(0, aligned.as_ptr().addr() % SIMD_ALIGN_BYTES);
+        let mut bytes = vec![0u8; num_bytes];
+        expected.get_bytes(&mut bytes, num_bytes)?;
+        assert_eq!(bytes.as_slice(), &*aligned);
+    }
+    Ok(())
+}
+
+#[test]
+fn shake128_get_bytes_huge_request_errors() -> Result<()> {
+    let mut hasher = Shake128::new();
+    hasher.finalize()?;
+    let mut result = [0u8; 4];
+    assert!(hasher.get_bytes(&mut result, usize::MAX).is_err());
+    assert!(hasher.get_bytes(&mut result, 5).is_err());
+    hasher.get_bytes(&mut result, 4)?;
+    assert_eq!([0x7F, 0x9C, 0x2B, 0xA4], result);
+    Ok(())
+}
+
+#[test]
+fn shake128_verify_xof_stream() -> Result<()> {
+    let mut hasher = Shake128::new();
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize()?;
+    let mut tag = vec![0u8; 200];
+    hasher.get_bytes(&mut tag, 200)?;
+
+    let mut hasher = Shake128::new();
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize()?;
+    assert!(hasher.verify_xof_stream(&mut Cursor::new(&tag), 200)?);
+
+    tag[150] ^= 0x01;
+    let mut hasher = Shake128::new();
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize()?;
+    assert!(!hasher.verify_xof_stream(&mut Cursor::new(&tag), 200)?);
+
+    let mut hasher = Shake128::new();
+    hasher.finalize()?;
+    assert!(
+        hasher
+            .verify_xof_stream(&mut Cursor::new(&tag[..10]), 20)
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn shake128_get_exact() -> Result<()> {
+    let mut expected = Shake128::new();
+    expected.update(b"Hello, world!")?;
+    let expected = expected.take(37).collect::<Vec<u8>>();
+
+    let mut hasher = Shake128::new();
+    hasher.update(b"Hello, world!")?;
+    hasher.finalize()?;
+    let mut output = [0u8; 37];
+    hasher.get_exact(&mut output)?;
+    assert_eq!(expected, output);
+    Ok(())
+}
+
+#[test]
+fn shake128_get_bits_exact() -> Result<()> {
+    let mut hasher = Shake128::new();
+    hasher.finalize()?;
+    let mut output = BitVec::<u8, Lsb0>::new();
+    hasher.get_bits_exact(&mut output, 3)?;
+    hasher.get_bits_exact(&mut output, 21)?;
+    assert_eq!(24, output.len());
+    assert_eq!("7f9c2b", b2h(&output, false, false)?);
+    Ok(())
+}
+
+#[test]
+fn shake128_finalize_tag_minimum_length() -> Result<()> {
+    let mut expected = Shake128::new();
+    expected.update(b"Hello, world!")?;
+
+    let mut hasher = Shake128::new();
+    hasher.update(b"Hello, world!")?;
+    assert!(hasher.finalize_tag(8).is_err());
+    assert_eq!(
+        expected.take(64).collect::<Vec<u8>>(),
+        hasher.finalize_tag(64)?
+    );
+    Ok(())
+}
+
+#[test]
+fn shake128_verify_long_tag() -> Result<()> {
+    const TAG_BYTES: usize = 1 << 16;
+
+    let mut keyed = Shake128::new();
+    keyed.update(&[0x42; 32])?;
+    keyed.update(b"a message with a very long tag")?;
+    keyed.finalize()?;
+
+    let mut tag = vec![0u8; TAG_BYTES];
+    keyed.clone().get_bytes(&mut tag, TAG_BYTES)?;
+    assert!(
+        keyed
+            .clone()
+            .verify_long_tag(&mut Cursor::new(&tag), TAG_BYTES)?
+    );
+
+    tag[TAG_BYTES - 1000] ^= 0x10;
+    assert!(
+        !keyed
+            .clone()
+            .verify_long_tag(&mut Cursor::new(&tag), TAG_BYTES)?
+    );
+
+    assert!(
+        keyed
+            .verify_long_tag(&mut Cursor::new(&tag[..100]), 200)
+            .is_err()
+    );
+    Ok(())
+}
+
+type Absorb<'a> = dyn Fn(&mut Shake128) -> Result<()> + 'a;
+
+#[test]
+fn shake128_update_helpers() -> Result<()> {
+    let data = (0..=250).cycle().take(200).collect::<Vec<u8>>();
+    let bits = &BitVec::<u8, Lsb0>::from_slice(&data)[..1597];
+
+    // Each helper is compared against the plain `update` (or `update_bits`) it stands for
+    let cases: [(&str, &Absorb<'_>, &Absorb<'_>); 6] = [
+        (
+            "update_any",
+            &|hasher| {
+                hasher.update_any("Hello, ")?;
+                hasher.update_any(String::from("wor"))?;
+                hasher.update_any(vec![b'l', b'd'])?;
+                hasher.update_any([0x21u8; 1])
+            },
+            &|hasher| hasher.update(b"Hello, world!"),
+        ),
+        (
+            "update_io_slices",
+            &|hasher| hasher.update_io_slices(&[IoSlice::new(b"Hello, "), IoSlice::new(b"world!")]),
+            &|hasher| hasher.update(b"Hello, world!"),
+        ),
+        (
+            "update_hex",
+            &|hasher| hasher.update_hex("48 65 6C 6C 6F"),
+            &|hasher| hasher.update(b"Hello"),
+        ),
+        (
+            "update_fixed_width_integers",
+            &|hasher| {
+                hasher.update_u64_le(0x0102_0304_0506_0708)?;
+                hasher.update_u64_be(0x0102_0304_0506_0708)
+            },
+            &|hasher| {
+                hasher.update(&[0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01])?;
+                hasher.update(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08])
+            },
+        ),
+        (
+            "update_bits_iter",
+            &|hasher| hasher.update_bits_iter([&bits[..5], &bits[5..1350], &bits[1350..]]),
+            &|hasher| hasher.update_bits(bits),
+        ),
+        (
+            "update_with_trailing_bits",
+            &|hasher| hasher.update_with_trailing_bits(&data[..199], data[199], 5),
+            &|hasher| hasher.update_bits(bits),
+        ),
+    ];
+
+    for (name, helper, plain) in cases {
+        let mut hasher = Shake128::new();
+        helper(&mut hasher)?;
+        let mut expected = Shake128::new();
+        plain(&mut expected)?;
+        assert_eq!(
+            expected.take(32).collect::<Vec<u8>>(),
+            hasher.take(32).collect::<Vec<u8>>(),
+            "{name}"
+        );
+    }
+    Ok(())
+}
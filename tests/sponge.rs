@@ -0,0 +1,242 @@
+#![cfg(feature = "low-level")]
+
+use anyhow::Result;
+use shashasha::{
+    BitVec, Endian, Hasher, Keccak1600Sponge, LANE_COUNT, Lsb0, SHA3_256_BYTES, Sha3_256, Shake128,
+    XofHasher, bits, nist_format,
+};
+
+#[test]
+fn sponge_reproduces_sha3_256() -> Result<()> {
+    let mut sponge = Keccak1600Sponge::new(1088, 512);
+    let mut result = [0u8; SHA3_256_BYTES];
+    sponge.update(b"ab")?;
+    sponge.update_bits(bits![u8, Lsb0; 1, 1, 0, 0, 0, 1, 1, 0])?;
+    sponge.finalize(bits![u8, Lsb0; 0, 1])?;
+    sponge.squeeze_bytes(&mut result)?;
+
+    let mut hasher = Sha3_256::new();
+    let mut expected = [0u8; SHA3_256_BYTES];
+    hasher.update(b"abc")?;
+    hasher.finalize(&mut expected)?;
+    assert_eq!(nist_format(&expected), nist_format(&result));
+    Ok(())
+}
+
+#[test]
+fn sponge_squeezes_like_shake128() -> Result<()> {
+    let mut sponge = Keccak1600Sponge::new(1344, 256);
+    sponge.update(b"abc")?;
+    sponge.finalize(bits![u8, Lsb0; 1, 1, 1, 1])?;
+    // Squeeze across a block boundary, mixing bytes and bits
+    let mut bytes = [0u8; 200];
+    sponge.squeeze_bytes(&mut bytes)?;
+    let mut bits = BitVec::<u8, Lsb0>::new();
+    sponge.squeeze_bits(&mut bits, 16)?;
+
+    let mut hasher = Shake128::new();
+    hasher.update(b"abc")?;
+    hasher.finalize()?;
+    let expected = hasher.take(202).collect::<Vec<u8>>();
+    assert_eq!(&expected[..200], &bytes[..]);
+    assert_eq!(&expected[200..], bits.as_raw_slice());
+    Ok(())
+}
+
+#[test]
+fn sponge_squeeze_requires_finalize() -> Result<()> {
+    let mut sponge = Keccak1600Sponge::new(1088, 512);
+    sponge.update(b"abc")?;
+    let mut output = [0u8; 4];
+    assert!(sponge.squeeze_bytes(&mut output).is_err());
+    assert!(sponge.squeeze_bits(&mut BitVec::new(), 4).is_err());
+
+    sponge.finalize(bits![u8, Lsb0; 0, 1])?;
+    assert!(sponge.update(b"more").is_err());
+    assert!(sponge.finalize(bits![u8, Lsb0; 0, 1]).is_err());
+    sponge.squeeze_bytes(&mut output)?;
+    Ok(())
+}
+
+#[test]
+fn sponge_new_bytes_reproduces_sha3_256() -> Result<()> {
+    let mut sponge = Keccak1600Sponge::new_bytes(136, 64)?;
+    let mut result = [0u8; SHA3_256_BYTES];
+    sponge.update(b"abc")?;
+    sponge.finalize(bits![u8, Lsb0; 0, 1])?;
+    sponge.squeeze_bytes(&mut result)?;
+    assert_eq!(
+        "3A 98 5D A7 4F E2 25 B2 04 5C 17 2D 6B D3 90 BD 85 5F 08 6E 3E 9D 52 5B 46 BF E2 45 11 43 15 32",
+        nist_format(&result)
+    );
+    Ok(())
+}
+
+#[test]
+fn sponge_big_endian_lane_packing() -> Result<()> {
+    for (message, expected) in [
+        (
+            &b""[..],
+            "9F 29 A5 8D 4D 8C 44 37 4D A5 6E 6B F8 EE 63 C5 83 B0 F8 FE 59 3C 97 35 87 BA 7E B1 84 81 23 B5",
+        ),
+        (
+            &b"abc"[..],
+            "75 4D 4B 4C B8 BC 0A 36 C6 EE 78 36 A7 8E BE 95 CE 79 40 7F F2 1C 35 02 77 2B 57 5E 1A 63 29 7B",
+        ),
+    ] {
+        let mut sponge = Keccak1600Sponge::new_with_endian(1088, 512, Endian::Big);
+        let mut result = [0u8; SHA3_256_BYTES];
+        sponge.update(message)?;
+        sponge.finalize(bits![u8, Lsb0; 0, 1])?;
+        sponge.squeeze_bytes(&mut result)?;
+        assert_eq!(expected, nist_format(&result));
+
+        // Little-endian packing is the standard SHA3-256
+        let mut sponge = Keccak1600Sponge::new_with_endian(1088, 512, Endian::Little);
+        let mut little = [0u8; SHA3_256_BYTES];
+        sponge.update(message)?;
+        sponge.finalize(bits![u8, Lsb0; 0, 1])?;
+        sponge.squeeze_bytes(&mut little)?;
+
+        let mut hasher = Sha3_256::new();
+        let mut expected = [0u8; SHA3_256_BYTES];
+        hasher.update(message)?;
+        hasher.finalize(&mut expected)?;
+        assert_eq!(expected, little);
+        assert_ne!(little, result);
+    }
+    Ok(())
+}
+
+#[test]
+fn sponge_buffered_output_bits() -> Result<()> {
+    let mut sponge = Keccak1600Sponge::new(1344, 256);
+    assert_eq!(0, sponge.buffered_output_bits());
+    sponge.finalize(bits![u8, Lsb0; 1, 1, 1, 1])?;
+    // Finalizing fills the output buffer with the first block
+    assert_eq!(1344, sponge.buffered_output_bits());
+
+    // 1500 bits needs one refill beyond the first block
+    let mut output = BitVec::<u8, Lsb0>::new();
+    sponge.squeeze_bits(&mut output, 1500)?;
+    assert_eq!(2 * 1344 - 1500, sponge.buffered_output_bits());
+
+    // Draining the buffer exactly does not refill it early
+    let remaining = sponge.buffered_output_bits();
+    sponge.squeeze_bits(&mut output, remaining)?;
+    assert_eq!(0, sponge.buffered_output_bits());
+    Ok(())
+}
+
+#[test]
+fn sponge_last_padded_block() -> Result<()> {
+    let mut sponge = Keccak1600Sponge::new(1088, 512);
+    assert!(sponge.last_padded_block().is_none());
+    sponge.update(b"abc")?;
+    assert!(sponge.last_padded_block().is_none());
+    sponge.finalize(bits![u8, Lsb0; 0, 1])?;
+
+    // The message, the SHA-3 suffix `01`, then `pad10*1` out to the rate
+    let mut expected = BitVec::<u8, Lsb0>::from_slice(b"abc");
+    expected.extend_from_bitslice(bits![u8, Lsb0; 0, 1, 1]);
+    expected.resize(1087, false);
+    expected.push(true);
+    assert_eq!(Some(expected.as_bitslice()), sponge.last_padded_block());
+    // As bytes this is the familiar 0x06 ... 0x80 SHA-3 padding
+    let block = sponge.last_padded_block().map(BitVec::from_bitslice);
+    let bytes = block.as_ref().map(BitVec::as_raw_slice).unwrap_or_default();
+    assert_eq!(&[b'a', b'b', b'c', 0x06], &bytes[..4]);
+    assert!(bytes[4..135].iter().all(|&byte| byte == 0));
+    assert_eq!(0x80, bytes[135]);
+
+    // One bit short of the rate, the padding spills into a block of zeros ending in `1`
+    let mut sponge = Keccak1600Sponge::new(1088, 512);
+    sponge.update_bits(&BitVec::<u8, Lsb0>::repeat(true, 1087))?;
+    sponge.finalize(bits![u8, Lsb0;])?;
+    let mut expected = BitVec::<u8, Lsb0>::repeat(false, 1087);
+    expected.push(true);
+    assert_eq!(Some(expected.as_bitslice()), sponge.last_padded_block());
+    Ok(())
+}
+
+#[test]
+fn sponge_rejects_rate_wider_than_state() -> Result<()> {
+    let block = BitVec::<u8, Lsb0>::repeat(true, 1700);
+    let mut sponge = Keccak1600Sponge::new(1700, 0);
+    assert!(sponge.absorb_raw_block(&block).is_err());
+    assert!(sponge.update(&[0xff; 256]).is_err());
+    assert!(sponge.update_bits(&block).is_err());
+    assert!(sponge.finalize(bits![u8, Lsb0; 0, 1]).is_err());
+    assert_eq!(&[0u64; 25], sponge.state());
+
+    // The full state width is still a valid (if insecure) rate
+    let mut sponge = Keccak1600Sponge::new(1600, 0);
+    sponge.absorb_raw_block(&BitVec::<u8, Lsb0>::repeat(true, 1600))?;
+    Ok(())
+}
+
+#[test]
+fn sponge_hashes_from_custom_iv() -> Result<()> {
+    let digest = |mut sponge: Keccak1600Sponge| -> Result<[u8; SHA3_256_BYTES]> {
+        let mut output = [0u8; SHA3_256_BYTES];
+        sponge.update(b"initial state")?;
+        sponge.finalize(bits![u8, Lsb0; 0, 1])?;
+        sponge.squeeze_bytes(&mut output)?;
+        Ok(output)
+    };
+
+    // An all-zero IV is the standard SHA3-256
+    let mut hasher = Sha3_256::new();
+    let mut standard = [0u8; SHA3_256_BYTES];
+    hasher.update(b"initial state")?;
+    hasher.finalize(&mut standard)?;
+    let zero = Keccak1600Sponge::new_with_iv(1088, 512, [0; LANE_COUNT]);
+    assert_eq!(standard, digest(zero)?);
+
+    // Even a single bit in the capacity changes the output, deterministically
+    let mut iv = [0u64; LANE_COUNT];
+    iv[LANE_COUNT - 1] = 1;
+    let seeded = digest(Keccak1600Sponge::new_with_iv(1088, 512, iv))?;
+    assert_ne!(standard, seeded);
+    assert_eq!(
+        seeded,
+        digest(Keccak1600Sponge::new_with_iv(1088, 512, iv))?
+    );
+
+    // The digest is the first lanes of the IV after absorbing the padded block
+    let mut block = BitVec::<u8, Lsb0>::from_slice(b"initial state");
+    block.extend_from_bitslice(bits![u8, Lsb0; 0, 1, 1]);
+    block.resize(1087, false);
+    block.push(true);
+    let mut sponge = Keccak1600Sponge::new_with_iv(1088, 512, iv);
+    sponge.absorb_raw_block(&block)?;
+    let lanes = sponge.state()[..4]
+        .iter()
+        .flat_map(|lane| lane.to_le_bytes())
+        .collect::<Vec<u8>>();
+    assert_eq!(&seeded[..], &lanes[..]);
+    Ok(())
+}
+
+#[test]
+fn sponge_raw_blocks_follow_buffered_updates() -> Result<()> {
+    let block = BitVec::<u8, Lsb0>::repeat(true, 1088);
+
+    // A partial block is still buffered, so the raw block would jump ahead of it
+    let mut sponge = Keccak1600Sponge::new(1088, 512);
+    sponge.update(b"abc")?;
+    assert!(sponge.absorb_raw_block(&block).is_err());
+    assert_eq!(&[0u64; LANE_COUNT], sponge.state());
+
+    // On a block boundary the updates and the raw block are absorbed in order
+    let message = [0x5a; 136];
+    let mut mixed = Keccak1600Sponge::new(1088, 512);
+    mixed.update(&message)?;
+    mixed.absorb_raw_block(&block)?;
+
+    let mut raw = Keccak1600Sponge::new(1088, 512);
+    raw.absorb_raw_block(&BitVec::<u8, Lsb0>::from_slice(&message))?;
+    raw.absorb_raw_block(&block)?;
+    assert_eq!(raw.state(), mixed.state());
+    Ok(())
+}